@@ -0,0 +1,70 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use aoc::config::Config;
+use reqwest::blocking::Client;
+
+/// Fetches pages from the advent of code site, or from a local mirror of it.
+///
+/// A base url of the form `file:///some/dir` maps every request onto the file
+/// system, so `/2022/day/7/input` is read from `/some/dir/2022/day/7/input` and
+/// `/2022/day/7` from `/some/dir/2022/day/7/index.html`. This lets a saved copy
+/// of a puzzle page stand in for the real thing.
+pub struct AocClient {
+    base_url: String,
+    config: Config,
+    client: Client,
+}
+
+impl AocClient {
+    pub fn new(config: &Config) -> Self {
+        AocClient {
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            config: config.clone(),
+            client: Client::new(),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Result<String> {
+        if let Some(dir) = self.local_dir() {
+            let mut fname = dir.join(path.trim_start_matches('/'));
+            if fname.is_dir() {
+                fname = fname.join("index.html");
+            }
+            return fs::read_to_string(&fname)
+                .context(format!("Failed to read local page: {:?}", fname));
+        }
+
+        let response = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .header("cookie", format!("session={}", self.config.session()?))
+            .send()?
+            .error_for_status()?;
+
+        Ok(response.text()?)
+    }
+
+    /// Posts a form. With a local base url the file at `path` is returned as
+    /// the canned response instead.
+    pub fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        if self.local_dir().is_some() {
+            return self.get(path);
+        }
+
+        let response = self
+            .client
+            .post(format!("{}{}", self.base_url, path))
+            .header("cookie", format!("session={}", self.config.session()?))
+            .form(form)
+            .send()?
+            .error_for_status()?;
+
+        Ok(response.text()?)
+    }
+
+    fn local_dir(&self) -> Option<&Path> {
+        self.base_url.strip_prefix("file://").map(Path::new)
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{Context, Error, Result};
+use serde::Deserialize;
+
+use aoc::config::Config;
+
+use crate::client::AocClient;
+use crate::schedule::{format_duration, unlock_time};
+
+const DAYS: u32 = 25;
+const PARTS: [u32; 2] = [1, 2];
+
+/// Prints rankings and star timelines for a private leaderboard, given either
+/// a saved json file or the leaderboard id to fetch.
+pub fn show(config: &Config, client: &AocClient, source: &str) -> Result<()> {
+    let json = if Path::new(source).is_file() {
+        fs::read_to_string(source)?
+    } else {
+        client.get(&format!(
+            "/{}/leaderboard/private/view/{source}.json",
+            config.year
+        ))?
+    };
+    let leaderboard = json.parse::<Leaderboard>()?;
+
+    for scoring in Scoring::ALL {
+        println!("== {} ==", scoring);
+        for (rank, (member, score)) in scoring.ranking(&leaderboard).iter().enumerate() {
+            println!("{:>3}) {:>10}  {}", rank + 1, score, member);
+        }
+        println!();
+    }
+
+    for member in leaderboard.members_by_name() {
+        println!("== {} ==", member);
+        println!(
+            "{:>3}  {:>9}  {:>9}  {:>9}",
+            "day", "part 1", "part 2", "delta"
+        );
+        for day in 1..=DAYS {
+            let [part1, part2] = PARTS.map(|part| leaderboard.solve_time(member, day, part));
+            if part1.is_none() {
+                continue;
+            }
+            let delta = member.delta(day);
+            let [part1, part2, delta] =
+                [part1, part2, delta].map(|t| t.map(format_duration).unwrap_or_default());
+            println!("{:>3}  {:>9}  {:>9}  {:>9}", day, part1, part2, delta);
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub completion_day_level: HashMap<String, HashMap<String, Star>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Star {
+    pub get_star_ts: u64,
+}
+
+impl FromStr for Leaderboard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let leaderboard =
+            serde_json::from_str::<Leaderboard>(s).context("Failed to parse leaderboard json")?;
+        leaderboard
+            .event
+            .parse::<u32>()
+            .context(format!("Event {:?} is not a year", leaderboard.event))?;
+
+        Ok(leaderboard)
+    }
+}
+
+impl Leaderboard {
+    /// Checked to be a number when parsing.
+    fn year(&self) -> u32 {
+        self.event.parse().unwrap()
+    }
+
+    fn members_by_name(&self) -> Vec<&Member> {
+        let mut members = self.members.values().collect::<Vec<_>>();
+        members.sort_by_key(|m| (m.to_string(), m.id));
+        members
+    }
+
+    /// Seconds from the puzzle unlocking until `member` got the star.
+    pub fn solve_time(&self, member: &Member, day: u32, part: u32) -> Option<u64> {
+        member
+            .star_ts(day, part)
+            .map(|ts| ts.saturating_sub(unlock_time(self.year(), day)))
+    }
+}
+
+impl Member {
+    pub fn star_ts(&self, day: u32, part: u32) -> Option<u64> {
+        self.completion_day_level
+            .get(&day.to_string())?
+            .get(&part.to_string())
+            .map(|star| star.get_star_ts)
+    }
+
+    /// Seconds between getting the first and the second star of `day`, none
+    /// if the timestamps have the second star first.
+    pub fn delta(&self, day: u32) -> Option<u64> {
+        self.star_ts(day, 2)?.checked_sub(self.star_ts(day, 1)?)
+    }
+
+    /// Timestamp of the most recent star, used to break ties.
+    fn last_star(&self) -> i64 {
+        self.stars()
+            .map(|(_, _, ts)| ts as i64)
+            .max()
+            .unwrap_or(i64::MAX)
+    }
+
+    fn stars(&self) -> impl Iterator<Item = (u32, u32, u64)> + '_ {
+        (1..=DAYS).flat_map(move |day| {
+            PARTS
+                .iter()
+                .filter_map(move |&part| Some((day, part, self.star_ts(day, part)?)))
+        })
+    }
+}
+
+impl Display for Member {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "(anonymous user #{})", self.id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scoring {
+    /// The site's own scoring: each star is worth one point per member that
+    /// got it later, or didn't get it at all. Ties go to the earliest last star.
+    Local,
+    /// Number of stars, ties broken by who got their last star first.
+    Stars,
+    /// Total time from unlock to star, over all stars.
+    Speed,
+    /// Total time between part 1 and part 2, over all completed days.
+    Delta,
+}
+
+impl Scoring {
+    pub const ALL: [Scoring; 4] = [
+        Scoring::Local,
+        Scoring::Stars,
+        Scoring::Speed,
+        Scoring::Delta,
+    ];
+
+    /// Members from first to last, along with their displayed score.
+    pub fn ranking<'a>(&self, leaderboard: &'a Leaderboard) -> Vec<(&'a Member, String)> {
+        let members = leaderboard.members_by_name();
+
+        // Every scheme sorts ascending on a (primary, secondary) key.
+        let mut ranked = match self {
+            Scoring::Local => {
+                let mut scores = HashMap::<u64, i64>::new();
+                for day in 1..=DAYS {
+                    for part in PARTS {
+                        let mut finishers = members
+                            .iter()
+                            .filter_map(|m| Some((m.star_ts(day, part)?, m.id)))
+                            .collect::<Vec<_>>();
+                        finishers.sort();
+                        for (rank, (_, id)) in finishers.into_iter().enumerate() {
+                            *scores.entry(id).or_default() += (members.len() - rank) as i64;
+                        }
+                    }
+                }
+                members
+                    .into_iter()
+                    .map(|m| {
+                        let score = scores.get(&m.id).copied().unwrap_or_default();
+                        (m, (-score, m.last_star()), score.to_string())
+                    })
+                    .collect::<Vec<_>>()
+            }
+            Scoring::Stars => members
+                .into_iter()
+                .map(|m| {
+                    let stars = m.stars().count() as i64;
+                    (m, (-stars, m.last_star()), stars.to_string())
+                })
+                .collect(),
+            Scoring::Speed => members
+                .into_iter()
+                .map(|m| {
+                    let times = m
+                        .stars()
+                        .filter_map(|(day, part, _)| leaderboard.solve_time(m, day, part))
+                        .collect::<Vec<_>>();
+                    let total = times.iter().sum::<u64>();
+                    (
+                        m,
+                        (-(times.len() as i64), total as i64),
+                        format_duration(total),
+                    )
+                })
+                .collect(),
+            Scoring::Delta => members
+                .into_iter()
+                .map(|m| {
+                    let deltas = (1..=DAYS)
+                        .filter_map(|day| m.delta(day))
+                        .collect::<Vec<_>>();
+                    let total = deltas.iter().sum::<u64>();
+                    (
+                        m,
+                        (-(deltas.len() as i64), total as i64),
+                        format_duration(total),
+                    )
+                })
+                .collect(),
+        };
+
+        ranked.sort_by_key(|(_, key, _)| *key);
+        ranked
+            .into_iter()
+            .map(|(member, _, score)| (member, score))
+            .collect()
+    }
+}
+
+impl Display for Scoring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Scoring::Local => "local score",
+            Scoring::Stars => "stars",
+            Scoring::Speed => "total solve time",
+            Scoring::Delta => "total part 2 delta",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Day 1 of 2022 unlocked at 1669870800.
+    const JSON: &str = r#"{
+        "event": "2022",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "alice", "stars": 2, "local_score": 6,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1669870900, "star_index": 1},
+                        "2": {"get_star_ts": 1669871500, "star_index": 2}
+                    }
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 2, "local_score": 2,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1669870850, "star_index": 1},
+                        "2": {"get_star_ts": 1669871600, "star_index": 2}
+                    }
+                }
+            },
+            "3": {
+                "id": 3, "name": "carol", "stars": 0, "local_score": 0,
+                "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    fn names(ranking: Vec<(&Member, String)>) -> Vec<(String, String)> {
+        ranking
+            .into_iter()
+            .map(|(m, score)| (m.to_string(), score))
+            .collect()
+    }
+
+    #[test]
+    fn timelines() {
+        let leaderboard = JSON.parse::<Leaderboard>().unwrap();
+        let alice = &leaderboard.members["1"];
+        assert_eq!(leaderboard.solve_time(alice, 1, 1), Some(100));
+        assert_eq!(leaderboard.solve_time(alice, 1, 2), Some(700));
+        assert_eq!(leaderboard.solve_time(alice, 2, 1), None);
+        assert_eq!(alice.delta(1), Some(600));
+
+        let swapped = serde_json::from_str::<Member>(
+            r#"{
+                "id": 4, "name": "dave",
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1669871500, "star_index": 2},
+                        "2": {"get_star_ts": 1669870900, "star_index": 1}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(swapped.delta(1), None);
+    }
+
+    #[test]
+    fn rankings() {
+        let leaderboard = JSON.parse::<Leaderboard>().unwrap();
+        let anonymous = "(anonymous user #2)".to_string();
+
+        assert_eq!(
+            names(Scoring::Local.ranking(&leaderboard)),
+            vec![
+                ("alice".to_string(), "5".to_string()),
+                (anonymous.clone(), "5".to_string()),
+                ("carol".to_string(), "0".to_string()),
+            ]
+        );
+        assert_eq!(
+            names(Scoring::Stars.ranking(&leaderboard))[0],
+            ("alice".to_string(), "2".to_string())
+        );
+        assert_eq!(
+            names(Scoring::Speed.ranking(&leaderboard))[0],
+            ("alice".to_string(), "00:13:20".to_string())
+        );
+        assert_eq!(
+            names(Scoring::Delta.ranking(&leaderboard))[0],
+            ("alice".to_string(), "00:10:00".to_string())
+        );
+    }
+}
@@ -1,17 +1,60 @@
 use std::fs::OpenOptions;
 use std::io::prelude::*;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::{cmp::Ordering, fs};
 
 use anyhow::{anyhow, Context, Result};
+use aoc::config::Config;
 use lazy_static::lazy_static;
 use regex::Regex;
-use reqwest::blocking::Client;
+
+use client::AocClient;
+use puzzle::Puzzle;
+use schedule::Stopwatch;
+use submit::Ledger;
+
+mod client;
+mod leaderboard;
+mod puzzle;
+mod schedule;
+mod submit;
 
 fn main() -> Result<()> {
-    let root_dir = get_root_dir()?;
+    let config = Config::load()?;
+    let root_dir = &config.root;
+    let client = AocClient::new(&config);
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    match args.first().map(String::as_str) {
+        None => next_day(&config, &client, false),
+        Some("--wait") => next_day(&config, &client, true),
+        Some("submit") => {
+            let aoc_file = match args.get(1) {
+                Some(name) => AocFile::try_from(format!("{name}.rs").as_str())?,
+                None => latest_file(&root_dir.join("src"))?,
+            };
+            submit::submit(&config, &client, &aoc_file)
+        }
+        Some("times") => {
+            let stopwatch = Stopwatch::load(root_dir.join(".stopwatch"))?;
+            stopwatch.report(&Ledger::load(root_dir.join(".ledger"))?);
+            Ok(())
+        }
+        Some("leaderboard") => {
+            let source = args.get(1).ok_or(anyhow!(
+                "Expected a leaderboard id or json file: 'leaderboard <ID|FILE>'"
+            ))?;
+            leaderboard::show(&config, &client, source)
+        }
+        Some(cmd) => Err(anyhow!(
+            "Unknown command: {}, expected '[--wait]', 'submit [dayNN-P]', 'times' or 'leaderboard <ID|FILE>'",
+            cmd
+        )),
+    }
+}
 
+fn next_day(config: &Config, client: &AocClient, wait: bool) -> Result<()> {
+    let root_dir = &config.root;
     let src_dir = root_dir.join("src");
     if !src_dir.exists() {
         Err(anyhow!("Source directory does not exist: {:?}", src_dir))?;
@@ -22,58 +65,108 @@ fn main() -> Result<()> {
         Err(anyhow!("Cargo.toml file not found!: {:?}", cargo_toml))?;
     }
 
-    let session_file = root_dir.join(".session");
-    if !session_file.exists() {
-        Err(anyhow!(".session file not found!: {:?}", session_file))?;
-    }
-
-    let mut aoc_files = fs::read_dir(&src_dir)?
-        .filter_map(|fname| match AocFile::try_from(fname.unwrap().path()) {
-            Ok(aoc_file) => Some(aoc_file),
-            Err(..) => None,
-        })
-        .collect::<Vec<_>>();
-    aoc_files.sort();
-
-    let prev_file = aoc_files.last().ok_or(anyhow!(
-        "No files found in source directory: {:?}?!",
-        src_dir
-    ))?;
+    let prev_file = latest_file(&src_dir)?;
     let next_file = prev_file.next();
 
-    fs::copy(
-        src_dir.join(&prev_file.fname),
-        src_dir.join(&next_file.fname),
-    )
-    .context(format!(
-        "Failed to copy {} to {}",
-        prev_file.fname, next_file.fname
-    ))?;
+    if wait && next_file.part == 1 {
+        schedule::wait_for_unlock(config.year, next_file.day)?;
+    }
+
+    let template = config.template();
+    let generate_tests = if next_file.part == 1 && template.exists() {
+        let source = fs::read_to_string(&template)?.replace("__DAY__", &next_file.day.to_string());
+        fs::write(src_dir.join(&next_file.fname), source)?;
+        true
+    } else {
+        fs::copy(
+            src_dir.join(&prev_file.fname),
+            src_dir.join(&next_file.fname),
+        )
+        .context(format!(
+            "Failed to copy {} to {}",
+            prev_file.fname, next_file.fname
+        ))?;
+        fs::read_to_string(src_dir.join(&next_file.fname))?.contains(puzzle::EXAMPLES_MARKER)
+    };
 
     add_section(&cargo_toml, &next_file)?;
     if next_file.part == 1 {
-        get_new_input(session_file, next_file.day)?;
+        get_new_input(config, client, next_file.day)?;
+        Stopwatch::load(root_dir.join(".stopwatch"))?.start(next_file.day, schedule::now()?)?;
     }
 
+    add_examples(config, client, &next_file, generate_tests)?;
+
+    Ok(())
+}
+
+fn latest_file(src_dir: &Path) -> Result<AocFile> {
+    fs::read_dir(src_dir)?
+        .filter_map(|fname| AocFile::try_from(fname.unwrap().path()).ok())
+        .max()
+        .ok_or(anyhow!(
+            "No files found in source directory: {:?}?!",
+            src_dir
+        ))
+}
+
+fn get_new_input(config: &Config, client: &AocClient, day: u32) -> Result<()> {
+    let new_input = config.input_file(day);
+    fs::create_dir_all(new_input.parent().unwrap())?;
+
+    fs::write(
+        new_input,
+        client.get(&format!("/{}/day/{day}/input", config.year))?,
+    )?;
+
     Ok(())
 }
 
-fn get_new_input(session_file: PathBuf, day: u32) -> Result<()> {
-    let session = fs::read_to_string(&session_file)?;
-    let session = session.trim();
+/// Saves the example from the puzzle page and, when the solution follows the
+/// template's `solution(fname)` convention, appends a test checking it against
+/// the expected answer.
+fn add_examples(
+    config: &Config,
+    client: &AocClient,
+    next_file: &AocFile,
+    generate_tests: bool,
+) -> Result<()> {
+    let puzzle = client
+        .get(&format!("/{}/day/{}", config.year, next_file.day))?
+        .parse::<Puzzle>()?;
 
-    let response = Client::new()
-        .get(format!("https://adventofcode.com/2022/day/{}/input", day))
-        .header("cookie", format!("session={}", session))
-        .send()?;
+    let example = puzzle.example(next_file.part).ok_or(anyhow!(
+        "No example found for day {} part {}",
+        next_file.day,
+        next_file.part
+    ))?;
+
+    let example_file = config
+        .examples_dir
+        .join(format!("day{:02}-{}.txt", next_file.day, next_file.part));
+    fs::create_dir_all(config.examples_dir())?;
+    fs::write(config.root.join(&example_file), example)?;
 
-    let new_input = session_file
-        .parent()
-        .unwrap()
-        .join("input")
-        .join(format!("day{:02}.txt", day));
+    if !generate_tests {
+        return Ok(());
+    }
+
+    let answer = puzzle.answer(next_file.part).ok_or(anyhow!(
+        "No answer found for day {} part {}, is it unlocked?",
+        next_file.day,
+        next_file.part
+    ))?;
 
-    fs::write(new_input, response.text()?)?;
+    let source_file = config.root.join("src").join(&next_file.fname);
+    let source = fs::read_to_string(&source_file)?;
+    fs::write(
+        &source_file,
+        format!(
+            "{}\n\n{}",
+            puzzle::strip_example_tests(&source).trim_end(),
+            puzzle::example_tests(&example_file.to_string_lossy(), answer)
+        ),
+    )?;
 
     Ok(())
 }
@@ -94,7 +187,7 @@ path = \"src/{fname}\"
     Ok(())
 }
 
-#[derive(Debug, PartialEq, PartialOrd, Eq)]
+#[derive(Debug, PartialEq, Eq)]
 struct AocFile {
     fname: String,
     day: u32,
@@ -107,6 +200,11 @@ impl AocFile {
         AocFile { fname, day, part }
     }
 
+    /// The cargo binary name, e.g. `day07-2`.
+    fn name(&self) -> String {
+        format!("day{:02}-{}", self.day, self.part)
+    }
+
     fn next(&self) -> Self {
         if self.part == 1 {
             AocFile::new(self.day, self.part + 1)
@@ -116,6 +214,12 @@ impl AocFile {
     }
 }
 
+impl PartialOrd for AocFile {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl Ord for AocFile {
     fn cmp(&self, other: &Self) -> Ordering {
         match self.day.cmp(&other.day) {
@@ -158,15 +262,3 @@ impl TryFrom<PathBuf> for AocFile {
         AocFile::try_from(s)
     }
 }
-
-fn get_root_dir() -> Result<PathBuf> {
-    let rootdir = String::from_utf8(
-        Command::new("git")
-            .arg("rev-parse")
-            .arg("--show-toplevel")
-            .output()?
-            .stdout,
-    )?;
-
-    Ok(PathBuf::from(rootdir.trim()))
-}
@@ -0,0 +1,186 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Line that marks the start of the generated example tests in a solution.
+/// Everything from here to the end of the file is rewritten on every scaffold.
+pub const EXAMPLES_MARKER: &str = "// --- examples generated by next_day ---";
+
+/// The parts of a puzzle page that are useful for testing: the example input
+/// and the highlighted answer of each part that has been unlocked so far.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Puzzle {
+    pub parts: Vec<Part>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Part {
+    pub example: Option<String>,
+    pub answer: Option<String>,
+}
+
+impl Puzzle {
+    /// Part two rarely has an example of its own and reuses the one before it.
+    pub fn example(&self, part: u32) -> Option<&str> {
+        self.parts
+            .iter()
+            .take(part as usize)
+            .rev()
+            .find_map(|p| p.example.as_deref())
+    }
+
+    pub fn answer(&self, part: u32) -> Option<&str> {
+        self.parts
+            .get(part.checked_sub(1)? as usize)?
+            .answer
+            .as_deref()
+    }
+}
+
+impl FromStr for Puzzle {
+    type Err = Error;
+
+    fn from_str(html: &str) -> Result<Self, Self::Err> {
+        lazy_static! {
+            static ref ARTICLE: Regex =
+                Regex::new(r#"(?s)<article class="day-desc">(.*?)</article>"#).unwrap();
+        }
+
+        let parts = ARTICLE
+            .captures_iter(html)
+            .map(|captures| Part::from_article(&captures[1]))
+            .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            Err(anyhow!("No puzzle description found in page!"))?;
+        }
+
+        Ok(Puzzle { parts })
+    }
+}
+
+impl Part {
+    /// The example is the first code block of the description and the answer
+    /// is the last emphasized code span, which is how the puzzles are written.
+    fn from_article(article: &str) -> Self {
+        lazy_static! {
+            static ref EXAMPLE: Regex = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+            static ref ANSWER: Regex =
+                Regex::new(r"<code><em>([^<]*)</em></code>|<em><code>([^<]*)</code></em>").unwrap();
+        }
+
+        let example = EXAMPLE
+            .captures(article)
+            .map(|captures| unescape(&captures[1]));
+
+        let answer = ANSWER
+            .captures_iter(article)
+            .last()
+            .and_then(|captures| captures.get(1).or(captures.get(2)))
+            .map(|m| unescape(m.as_str()).trim().to_string());
+
+        Part { example, answer }
+    }
+}
+
+/// Strips any nested markup and decodes the handful of entities the site uses.
+fn unescape(s: &str) -> String {
+    lazy_static! {
+        static ref TAG: Regex = Regex::new(r"<[^>]*>").unwrap();
+    }
+
+    TAG.replace_all(s, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Renders the test module appended to a solution, checking `solution` against
+/// the example file.
+pub fn example_tests(example_file: &str, answer: &str) -> String {
+    format!(
+        "{EXAMPLES_MARKER}
+
+#[cfg(test)]
+mod examples {{
+    use super::*;
+
+    use rstest::rstest;
+
+    #[rstest]
+    #[case({example_file:?}, {answer:?})]
+    fn example_tests(#[case] fname: &str, #[case] expected: &str) {{
+        assert_eq!(solution(fname).unwrap().to_string(), expected);
+    }}
+}}
+"
+    )
+}
+
+/// Removes previously generated example tests, e.g. when part 2 is copied from
+/// part 1.
+pub fn strip_example_tests(source: &str) -> &str {
+    match source.find(EXAMPLES_MARKER) {
+        Some(idx) => &source[..idx],
+        None => source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 6: Tuning Trouble ---</h2>
+<p>For example:</p>
+<pre><code><em>mjq</em>jpqm &lt;gb&gt;
+</code></pre>
+<p>Is <code>3</code> here, but the answer is <code><em>7</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1929</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Now it is <em><code>19</code></em>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn parse_page() {
+        let puzzle = Puzzle::from_str(PAGE).unwrap();
+        assert_eq!(
+            puzzle,
+            Puzzle {
+                parts: vec![
+                    Part {
+                        example: Some("mjqjpqm <gb>\n".to_string()),
+                        answer: Some("7".to_string()),
+                    },
+                    Part {
+                        example: None,
+                        answer: Some("19".to_string()),
+                    },
+                ]
+            }
+        );
+        assert_eq!(puzzle.example(2), Some("mjqjpqm <gb>\n"));
+        assert_eq!(puzzle.answer(2), Some("19"));
+        assert_eq!(puzzle.answer(3), None);
+    }
+
+    #[test]
+    fn parse_error_page() {
+        assert!(Puzzle::from_str("Please don't repeatedly request this endpoint").is_err());
+    }
+
+    #[test]
+    fn strip_generated() {
+        let source = format!(
+            "fn main() {{}}\n{}",
+            example_tests("examples/day06-1.txt", "7")
+        );
+        assert_eq!(strip_example_tests(&source), "fn main() {}\n");
+    }
+}
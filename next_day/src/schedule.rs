@@ -0,0 +1,158 @@
+use std::fs::{self, OpenOptions};
+use std::io::prelude::*;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::submit::{Ledger, Outcome};
+
+/// Puzzles unlock at midnight US Eastern, which is UTC-5 in December.
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+/// Seconds since the unix epoch at which the puzzle for `day` unlocks.
+pub fn unlock_time(year: u32, day: u32) -> u64 {
+    (days_from_civil(year as i64, 12, day as i64) as u64 * 24 + UNLOCK_HOUR_UTC) * 60 * 60
+}
+
+/// Days since 1970-01-01 of a proleptic gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+pub fn now() -> Result<u64> {
+    Ok(SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs())
+}
+
+/// Sleeps until the puzzle for `day` unlocks, printing a countdown meanwhile.
+pub fn wait_for_unlock(year: u32, day: u32) -> Result<()> {
+    let unlock = unlock_time(year, day);
+
+    loop {
+        let now = now()?;
+        if now >= unlock {
+            break;
+        }
+        print!(
+            "\rDay {} unlocks in {} ",
+            day,
+            format_duration(unlock - now)
+        );
+        std::io::stdout().flush()?;
+        thread::sleep(Duration::from_secs(1));
+    }
+    println!("\rDay {} is unlocked!{:20}", day, "");
+
+    Ok(())
+}
+
+pub fn format_duration(seconds: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// When work on each day started, stored as tab separated `day` and `start`
+/// lines. Solve times are measured against the right answers in the ledger.
+pub struct Stopwatch {
+    path: PathBuf,
+    starts: Vec<(u32, u64)>,
+}
+
+impl Stopwatch {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let starts = if path.exists() {
+            fs::read_to_string(&path)?
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    let (day, start) = line
+                        .split_once('\t')
+                        .ok_or(anyhow!("Malformed stopwatch line: {:?}", line))?;
+                    Ok((day.parse()?, start.parse()?))
+                })
+                .collect::<Result<Vec<_>>>()
+                .context(format!("Failed to read stopwatch: {:?}", path))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Stopwatch { path, starts })
+    }
+
+    /// Starts the clock for `day`, unless it is already running.
+    pub fn start(&mut self, day: u32, start: u64) -> Result<()> {
+        if self.start_of(day).is_some() {
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}\t{}", day, start)?;
+        self.starts.push((day, start));
+        Ok(())
+    }
+
+    pub fn start_of(&self, day: u32) -> Option<u64> {
+        self.starts
+            .iter()
+            .find(|(d, _)| *d == day)
+            .map(|(_, start)| *start)
+    }
+
+    /// Seconds from the start of `day` until `part` was answered correctly.
+    pub fn solve_time(&self, ledger: &Ledger, day: u32, part: u32) -> Option<u64> {
+        let start = self.start_of(day)?;
+        ledger
+            .attempts
+            .iter()
+            .find(|a| a.day == day && a.part == part && a.outcome == Outcome::Right)
+            .map(|a| a.timestamp.saturating_sub(start))
+    }
+
+    pub fn report(&self, ledger: &Ledger) {
+        println!("{:>3}  {:>9}  {:>9}", "day", "part 1", "part 2");
+        let mut days = self.starts.iter().map(|(day, _)| *day).collect::<Vec<_>>();
+        days.sort();
+        for day in days {
+            let times = [1, 2].map(|part| {
+                self.solve_time(ledger, day, part)
+                    .map(format_duration)
+                    .unwrap_or_else(|| "-".to_string())
+            });
+            println!("{:>3}  {:>9}  {:>9}", day, times[0], times[1]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlock_times() {
+        // 2022-12-01T05:00:00Z
+        assert_eq!(unlock_time(2022, 1), 1669870800);
+        assert_eq!(unlock_time(2022, 25), 1669870800 + 24 * 24 * 60 * 60);
+        // 2020-12-01T05:00:00Z
+        assert_eq!(unlock_time(2020, 1), 1606798800);
+    }
+
+    #[test]
+    fn format_durations() {
+        assert_eq!(format_duration(0), "00:00:00");
+        assert_eq!(format_duration(3 * 3600 + 25 * 60 + 7), "03:25:07");
+        assert_eq!(format_duration(30 * 3600), "30:00:00");
+    }
+}
@@ -0,0 +1,389 @@
+use std::fmt::Display;
+use std::fs::{self, OpenOptions};
+use std::io::prelude::*;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context, Error, Result};
+
+use aoc::config::Config;
+
+use crate::client::AocClient;
+use crate::schedule::{self, format_duration, Stopwatch};
+use crate::AocFile;
+
+/// Runs the solution for `aoc_file`, submits its answer and records the reply.
+pub fn submit(config: &Config, client: &AocClient, aoc_file: &AocFile) -> Result<()> {
+    let root_dir = &config.root;
+    let answer = run_solution(root_dir, aoc_file)?;
+
+    let mut ledger = Ledger::load(root_dir.join(".ledger"))?;
+    ledger.check(aoc_file.day, aoc_file.part, &answer)?;
+
+    let level = aoc_file.part.to_string();
+    let response = client.post(
+        &format!("/{}/day/{}/answer", config.year, aoc_file.day),
+        &[("level", &level), ("answer", &answer)],
+    )?;
+    let outcome = Outcome::from_response(&response)?;
+
+    ledger.record(Attempt {
+        day: aoc_file.day,
+        part: aoc_file.part,
+        answer: answer.clone(),
+        outcome,
+        timestamp: schedule::now()?,
+    })?;
+
+    println!("{}: {} is {}", aoc_file.name(), answer, outcome);
+
+    let stopwatch = Stopwatch::load(root_dir.join(".stopwatch"))?;
+    if let Some(time) = stopwatch.solve_time(&ledger, aoc_file.day, aoc_file.part) {
+        println!("Solved in {}", format_duration(time));
+    }
+
+    Ok(())
+}
+
+/// The answer is the first line `time_it` prints.
+fn run_solution(root_dir: &Path, aoc_file: &AocFile) -> Result<String> {
+    let output = Command::new("cargo")
+        .args(["run", "--release", "--quiet", "--bin", &aoc_file.name()])
+        .current_dir(root_dir)
+        .output()?;
+
+    if !output.status.success() {
+        Err(anyhow!(
+            "Failed to run {}:\n{}",
+            aoc_file.name(),
+            String::from_utf8_lossy(&output.stderr)
+        ))?;
+    }
+
+    String::from_utf8(output.stdout)?
+        .lines()
+        .next()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .ok_or(anyhow!("{} did not print an answer", aoc_file.name()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Right,
+    Wrong,
+    TooHigh,
+    TooLow,
+    RateLimited,
+    /// The part was already solved or isn't unlocked yet.
+    WrongLevel,
+}
+
+impl Outcome {
+    fn from_response(html: &str) -> Result<Self> {
+        if html.contains("That's the right answer") {
+            Ok(Outcome::Right)
+        } else if html.contains("You gave an answer too recently") {
+            Ok(Outcome::RateLimited)
+        } else if html.contains("You don't seem to be solving the right level") {
+            Ok(Outcome::WrongLevel)
+        } else if html.contains("your answer is too high") {
+            Ok(Outcome::TooHigh)
+        } else if html.contains("your answer is too low") {
+            Ok(Outcome::TooLow)
+        } else if html.contains("That's not the right answer") {
+            Ok(Outcome::Wrong)
+        } else {
+            Err(anyhow!("Unrecognized response to submission:\n{}", html))
+        }
+    }
+
+    /// Whether the reply says anything about the answer itself.
+    fn is_verdict(&self) -> bool {
+        !matches!(self, Outcome::RateLimited | Outcome::WrongLevel)
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Outcome::Right => "right",
+            Outcome::Wrong => "wrong",
+            Outcome::TooHigh => "too-high",
+            Outcome::TooLow => "too-low",
+            Outcome::RateLimited => "rate-limited",
+            Outcome::WrongLevel => "wrong-level",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(Outcome::Right),
+            "wrong" => Ok(Outcome::Wrong),
+            "too-high" => Ok(Outcome::TooHigh),
+            "too-low" => Ok(Outcome::TooLow),
+            "rate-limited" => Ok(Outcome::RateLimited),
+            "wrong-level" => Ok(Outcome::WrongLevel),
+            _ => Err(anyhow!("Unknown outcome: {}", s)),
+        }
+    }
+}
+
+/// One submission, stored as a tab separated line in the ledger, with tabs,
+/// newlines and backslashes in the answer escaped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub outcome: Outcome,
+    pub timestamp: u64,
+}
+
+impl Display for Attempt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}",
+            self.day,
+            self.part,
+            escape(&self.answer),
+            self.outcome,
+            self.timestamp
+        )
+    }
+}
+
+impl FromStr for Attempt {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split('\t').collect::<Vec<_>>();
+        let [day, part, answer, outcome, timestamp] = fields[..] else {
+            Err(anyhow!("Malformed ledger line: {:?}", s))?
+        };
+
+        Ok(Attempt {
+            day: day.parse()?,
+            part: part.parse()?,
+            answer: unescape(answer)?,
+            outcome: outcome.parse()?,
+            timestamp: timestamp.parse()?,
+        })
+    }
+}
+
+fn escape(answer: &str) -> String {
+    let mut escaped = String::with_capacity(answer.len());
+    for c in answer.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(field: &str) -> Result<String> {
+    let mut answer = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            answer.push(c);
+            continue;
+        }
+        answer.push(match chars.next() {
+            Some('\\') => '\\',
+            Some('t') => '\t',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            _ => Err(anyhow!("Malformed escape in ledger answer: {:?}", field))?,
+        });
+    }
+    Ok(answer)
+}
+
+/// Every answer ever submitted, used to avoid resubmitting known bad answers.
+pub struct Ledger {
+    path: PathBuf,
+    pub attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let attempts = if path.exists() {
+            fs::read_to_string(&path)?
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.parse())
+                .collect::<Result<Vec<_>>>()
+                .context(format!("Failed to read ledger: {:?}", path))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Ledger { path, attempts })
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", attempt)?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    /// Errors if `answer` is already known to be wrong, or is outside the range
+    /// left open by earlier too-high/too-low replies.
+    pub fn check(&self, day: u32, part: u32, answer: &str) -> Result<()> {
+        let attempts = self
+            .attempts
+            .iter()
+            .filter(|a| a.day == day && a.part == part)
+            .collect::<Vec<_>>();
+
+        if let Some(right) = attempts.iter().find(|a| a.outcome == Outcome::Right) {
+            Err(anyhow!(
+                "Day {} part {} was already solved with {}",
+                day,
+                part,
+                right.answer
+            ))?;
+        }
+
+        if let Some(known) = attempts
+            .iter()
+            .find(|a| a.answer == answer && a.outcome.is_verdict())
+        {
+            Err(anyhow!(
+                "{} is already known to be {}",
+                answer,
+                known.outcome
+            ))?;
+        }
+
+        let Ok(value) = answer.parse::<i64>() else {
+            return Ok(());
+        };
+
+        for attempt in attempts {
+            let Ok(bound) = attempt.answer.parse::<i64>() else {
+                continue;
+            };
+            match attempt.outcome {
+                Outcome::TooHigh if value >= bound => {
+                    Err(anyhow!("{} is too high, {} already was", answer, bound))?
+                }
+                Outcome::TooLow if value <= bound => {
+                    Err(anyhow!("{} is too low, {} already was", answer, bound))?
+                }
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ledger(attempts: &[(&str, Outcome)]) -> Ledger {
+        Ledger {
+            path: PathBuf::new(),
+            attempts: attempts
+                .iter()
+                .map(|(answer, outcome)| Attempt {
+                    day: 1,
+                    part: 2,
+                    answer: answer.to_string(),
+                    outcome: *outcome,
+                    timestamp: 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn parse_responses() {
+        let parse = Outcome::from_response;
+        assert_eq!(
+            parse("<p>That's the right answer!</p>").unwrap(),
+            Outcome::Right
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too high.").unwrap(),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            parse("That's not the right answer; your answer is too low.").unwrap(),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            parse("That's not the right answer.").unwrap(),
+            Outcome::Wrong
+        );
+        assert_eq!(
+            parse("You gave an answer too recently; wait 5m.").unwrap(),
+            Outcome::RateLimited
+        );
+        assert_eq!(
+            parse("You don't seem to be solving the right level.  Did you already complete it?")
+                .unwrap(),
+            Outcome::WrongLevel
+        );
+        assert!(parse("<html></html>").is_err());
+    }
+
+    #[test]
+    fn attempt_round_trip() {
+        let attempt = ledger(&[("abc", Outcome::Wrong)]).attempts.remove(0);
+        assert_eq!(attempt.to_string().parse::<Attempt>().unwrap(), attempt);
+
+        let attempt = Attempt {
+            answer: "a\tb\\t\nc\\".to_string(),
+            ..attempt
+        };
+        let line = attempt.to_string();
+        assert_eq!(line.split('\t').count(), 5);
+        assert_eq!(line.parse::<Attempt>().unwrap(), attempt);
+        assert!("1\t2\tbad\\\twrong\t0".parse::<Attempt>().is_err());
+    }
+
+    #[test]
+    fn check_answers() {
+        let ledger = ledger(&[
+            ("100", Outcome::TooHigh),
+            ("10", Outcome::TooLow),
+            ("50", Outcome::Wrong),
+            ("60", Outcome::RateLimited),
+            ("70", Outcome::WrongLevel),
+        ]);
+        assert!(ledger.check(1, 2, "100").is_err());
+        assert!(ledger.check(1, 2, "150").is_err());
+        assert!(ledger.check(1, 2, "5").is_err());
+        assert!(ledger.check(1, 2, "50").is_err());
+        assert!(ledger.check(1, 2, "60").is_ok());
+        assert!(ledger.check(1, 2, "70").is_ok());
+        assert!(ledger.check(1, 2, "99").is_ok());
+        assert!(ledger.check(1, 1, "150").is_ok());
+    }
+
+    #[test]
+    fn check_solved() {
+        let ledger = ledger(&[("42", Outcome::Right)]);
+        assert!(ledger.check(1, 2, "43").is_err());
+    }
+}
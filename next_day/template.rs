@@ -0,0 +1,16 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+
+use aoc::input_file;
+use aoc::time_it;
+
+fn main() -> Result<()> {
+    time_it(|| solution(input_file(__DAY__)?))
+}
+
+fn solution(fname: impl AsRef<Path>) -> Result<usize> {
+    let input = fs::read_to_string(fname)?;
+    Ok(input.lines().count())
+}
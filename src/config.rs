@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+/// Overrides `base_url`, e.g. to point `next_day` at a local mirror.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// Workspace settings shared by the solutions and `next_day`, read from
+/// `aoc.toml` at the root of the workspace. Missing keys take their defaults
+/// and relative paths are relative to the root. `AOC_BASE_URL` overrides
+/// `base_url` when it is set.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub year: u32,
+    pub input_dir: PathBuf,
+    pub examples_dir: PathBuf,
+    pub session: Session,
+    pub base_url: String,
+    pub template: PathBuf,
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+/// Where the session cookie comes from, e.g. `session = { env = "AOC_SESSION" }`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Session {
+    File(PathBuf),
+    Env(String),
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            year: 2022,
+            input_dir: PathBuf::from("input"),
+            examples_dir: PathBuf::from("examples"),
+            session: Session::File(PathBuf::from(".session")),
+            base_url: "https://adventofcode.com".to_string(),
+            template: PathBuf::from("next_day/template.rs"),
+            root: PathBuf::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config of the workspace containing the current directory.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&find_root(&std::env::current_dir()?))
+    }
+
+    pub fn load_from(root: &Path) -> Result<Self> {
+        let fname = root.join(CONFIG_FILE);
+        let mut config = if fname.exists() {
+            toml::from_str::<Config>(&std::fs::read_to_string(&fname)?)
+                .context(format!("Failed to parse {:?}", fname))?
+        } else {
+            Config::default()
+        };
+        config.root = root.to_path_buf();
+        if let Ok(base_url) = std::env::var(BASE_URL_VAR) {
+            config.base_url = base_url;
+        }
+
+        Ok(config)
+    }
+
+    pub fn input_file(&self, day: u32) -> PathBuf {
+        self.root
+            .join(&self.input_dir)
+            .join(format!("day{:02}.txt", day))
+    }
+
+    pub fn examples_dir(&self) -> PathBuf {
+        self.root.join(&self.examples_dir)
+    }
+
+    pub fn template(&self) -> PathBuf {
+        self.root.join(&self.template)
+    }
+
+    pub fn session(&self) -> Result<String> {
+        let session = match &self.session {
+            Session::File(fname) => {
+                let fname = self.root.join(fname);
+                std::fs::read_to_string(&fname)
+                    .context(format!("Failed to read session file: {:?}", fname))?
+            }
+            Session::Env(var) => {
+                std::env::var(var).map_err(|_| anyhow!("Session variable {} is not set", var))?
+            }
+        };
+
+        Ok(session.trim().to_string())
+    }
+}
+
+/// The workspace root is the closest directory holding an `aoc.toml`, then the
+/// top of the git checkout, and failing both the directory itself.
+pub fn find_root(dir: &Path) -> PathBuf {
+    if let Some(root) = dir.ancestors().find(|d| d.join(CONFIG_FILE).exists()) {
+        return root.to_path_buf();
+    }
+
+    Command::new("git")
+        .arg("rev-parse")
+        .arg("--show-toplevel")
+        .current_dir(dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|root| PathBuf::from(root.trim()))
+        .unwrap_or_else(|| dir.to_path_buf())
+}
+
+/// The config of the workspace containing the current directory, loaded the
+/// first time it is asked for.
+pub fn workspace() -> Result<&'static Config> {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    if let Some(config) = CONFIG.get() {
+        return Ok(config);
+    }
+    let config = Config::load()?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+/// Path of the puzzle input for `day`, e.g. `input/day07.txt`.
+pub fn input_file(day: u32) -> Result<PathBuf> {
+    Ok(workspace()?.input_file(day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = toml::from_str::<Config>(
+            r#"
+            year = 2021
+            input_dir = "data"
+            session = { env = "AOC_SESSION" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                year: 2021,
+                input_dir: PathBuf::from("data"),
+                session: Session::Env("AOC_SESSION".to_string()),
+                ..Config::default()
+            }
+        );
+        assert_eq!(config.input_file(7), PathBuf::from("data/day07.txt"));
+    }
+
+    #[test]
+    fn unknown_key() {
+        assert!(toml::from_str::<Config>("yeer = 2021").is_err());
+    }
+
+    #[test]
+    fn workspace_config() {
+        let config = Config::load().unwrap();
+        assert_eq!(config.root, PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+        assert!(config.input_file(1).exists());
+        assert!(std::ptr::eq(workspace().unwrap(), workspace().unwrap()));
+    }
+}
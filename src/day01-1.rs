@@ -1,5 +1,11 @@
+use std::fs::File;
+use std::io::BufReader;
+
 use anyhow::Result;
-use aoc::collect_lines;
+use itertools::process_results;
+
+use aoc::day01::{read_totals, top_k};
+use aoc::input_file;
 use aoc::time_it;
 
 fn main() -> Result<()> {
@@ -7,24 +13,12 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn solution() -> Result<u32> {
-    let data = collect_lines::<String>("input/day01.txt")?;
-
-    let mut max = 0u32;
-    let mut sum = 0u32;
-
-    for x in data {
-        if x.is_empty() {
-            if sum > max {
-                max = sum;
-            }
-            sum = 0;
-        } else {
-            sum = sum + x.parse::<u32>()?;
-        }
-    }
+fn solution() -> Result<u64> {
+    let reader = BufReader::new(File::open(input_file(1)?)?);
 
-    Ok(max)
+    process_results(read_totals(reader), |totals| {
+        top_k(totals, 1).into_iter().sum()
+    })
 }
 
 #[cfg(test)]
@@ -1,22 +1,38 @@
+use std::fs::File;
+use std::io::BufReader;
+
 use anyhow::Result;
+use itertools::process_results;
+
 use aoc::collect_blocks;
+use aoc::day01::{read_totals, top_k, Report};
+use aoc::input_file;
 use aoc::time_it;
-use itertools::sorted;
 
+const HISTOGRAM_BINS: usize = 10;
+
+/// Pass `--report` for per elf statistics, or `--csv` for the same as csv.
 fn main() -> Result<()> {
-    time_it(|| solution(3))?;
+    match std::env::args().nth(1).as_deref() {
+        Some("--report") => print!("{}", report()?),
+        Some("--csv") => print!("{}", report()?.to_csv()),
+        _ => time_it(|| solution(3))?,
+    }
     Ok(())
 }
 
-fn solution(take: usize) -> Result<u32> {
-    let blocks = collect_blocks::<u32>("input/day01.txt")?;
+fn solution(take: usize) -> Result<u64> {
+    let reader = BufReader::new(File::open(input_file(1)?)?);
 
-    let block_sums = blocks
-        .into_iter()
-        .map(|block| block.into_iter().sum())
-        .collect::<Vec<u32>>();
+    process_results(read_totals(reader), |totals| {
+        top_k(totals, take).into_iter().sum()
+    })
+}
 
-    Ok(sorted(block_sums).rev().take(take).sum())
+fn report() -> Result<Report> {
+    let blocks = collect_blocks::<u32>(input_file(1)?)?;
+
+    Ok(Report::new(blocks, HISTOGRAM_BINS))
 }
 
 #[cfg(test)]
@@ -32,4 +48,10 @@ mod tests {
     fn sln2() {
         assert_eq!(solution(3).unwrap(), 212117);
     }
+
+    #[test]
+    fn report_totals() {
+        let report = report().unwrap();
+        assert_eq!(report.percentile(100), Some(72511));
+    }
 }
@@ -0,0 +1,289 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt::Display;
+use std::io::BufRead;
+
+use anyhow::{anyhow, Result};
+
+/// The `k` largest values of `values`, largest first, keeping no more than `k`
+/// of them in memory at once.
+pub fn top_k<I>(values: I, k: usize) -> Vec<u64>
+where
+    I: IntoIterator<Item = u64>,
+{
+    let mut heap = BinaryHeap::new();
+    for value in values {
+        heap.push(Reverse(value));
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(value)| value)
+        .collect()
+}
+
+/// Calories carried by each elf, in input order.
+pub fn totals(blocks: Vec<Vec<u32>>) -> impl Iterator<Item = u64> {
+    blocks
+        .into_iter()
+        .map(|block| block.into_iter().map(|item| item as u64).sum())
+}
+
+/// Calories carried by each elf as the blank line separated blocks of items
+/// are read, without keeping more than one total in memory.
+pub fn read_totals(reader: impl BufRead) -> impl Iterator<Item = Result<u64>> {
+    let mut lines = reader.lines().enumerate();
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let mut total: Option<u64> = None;
+        loop {
+            match lines.next() {
+                None => {
+                    done = true;
+                    return total.map(Ok);
+                }
+                Some((_, Err(e))) => return Some(Err(e.into())),
+                Some((_, Ok(line))) if line.trim().is_empty() => {
+                    if total.is_some() {
+                        return total.map(Ok);
+                    }
+                }
+                Some((idx, Ok(line))) => {
+                    let item = match line.trim().parse::<u64>() {
+                        Ok(item) => item,
+                        Err(e) => return Some(Err(anyhow!("Line {}: {}", idx + 1, e))),
+                    };
+                    match total.unwrap_or(0).checked_add(item) {
+                        Some(sum) => total = Some(sum),
+                        None => return Some(Err(anyhow!("Line {}: total overflows", idx + 1))),
+                    }
+                }
+            }
+        }
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElfStats {
+    pub elf: usize,
+    pub count: usize,
+    pub total: u64,
+    pub mean: f64,
+    pub median: f64,
+}
+
+impl ElfStats {
+    fn new(elf: usize, mut items: Vec<u32>) -> Self {
+        items.sort();
+        let count = items.len();
+        let total = items.iter().map(|&item| item as u64).sum();
+
+        let (mean, median) = match count {
+            0 => (0.0, 0.0),
+            _ => (
+                total as f64 / count as f64,
+                (items[(count - 1) / 2] as f64 + items[count / 2] as f64) / 2.0,
+            ),
+        };
+
+        Self {
+            elf,
+            count,
+            total,
+            mean,
+            median,
+        }
+    }
+}
+
+/// Per elf statistics along with the distribution of their totals.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub elves: Vec<ElfStats>,
+    pub bins: usize,
+}
+
+const PERCENTILES: [u32; 5] = [25, 50, 75, 90, 99];
+const BAR_WIDTH: usize = 40;
+
+impl Report {
+    pub fn new(blocks: Vec<Vec<u32>>, bins: usize) -> Self {
+        let elves = blocks
+            .into_iter()
+            .enumerate()
+            .map(|(idx, items)| ElfStats::new(idx + 1, items))
+            .collect();
+
+        Self {
+            elves,
+            bins: bins.max(1),
+        }
+    }
+
+    /// Nearest-rank percentile of the elves' totals.
+    pub fn percentile(&self, p: u32) -> Option<u64> {
+        let mut totals = self.elves.iter().map(|e| e.total).collect::<Vec<_>>();
+        totals.sort();
+
+        let rank = (p as usize * totals.len()).div_ceil(100).max(1);
+        totals.get(rank - 1).copied()
+    }
+
+    /// Splits the range of totals into equal-width bins, returning the lower
+    /// bound of each bin and how many elves fall in it.
+    pub fn histogram(&self) -> Vec<(u64, usize)> {
+        let (Some(min), Some(max)) = (
+            self.elves.iter().map(|e| e.total).min(),
+            self.elves.iter().map(|e| e.total).max(),
+        ) else {
+            return Vec::new();
+        };
+
+        let width = ((max - min) / self.bins as u64).max(1);
+        let mut counts = vec![0; self.bins];
+        for elf in &self.elves {
+            let bin = ((elf.total - min) / width) as usize;
+            counts[bin.min(self.bins - 1)] += 1;
+        }
+
+        counts
+            .into_iter()
+            .enumerate()
+            .map(|(bin, count)| (min + bin as u64 * width, count))
+            .collect()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = "elf,count,total,mean,median\n".to_string();
+        for e in &self.elves {
+            csv += &format!(
+                "{},{},{},{:.2},{:.1}\n",
+                e.elf, e.count, e.total, e.mean, e.median
+            );
+        }
+        csv
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>5} {:>5} {:>8} {:>10} {:>10}",
+            "elf", "count", "total", "mean", "median"
+        )?;
+        for e in &self.elves {
+            writeln!(
+                f,
+                "{:>5} {:>5} {:>8} {:>10.2} {:>10.1}",
+                e.elf, e.count, e.total, e.mean, e.median
+            )?;
+        }
+
+        writeln!(f)?;
+        for p in PERCENTILES {
+            if let Some(total) = self.percentile(p) {
+                writeln!(f, "p{:<2} {:>8}", p, total)?;
+            }
+        }
+
+        writeln!(f)?;
+        let histogram = self.histogram();
+        let most = histogram.iter().map(|(_, count)| *count).max().unwrap_or(1);
+        for (lower, count) in histogram {
+            let bar = "#".repeat(count * BAR_WIDTH / most);
+            writeln!(f, "{:>8} {:>5} {}", lower, count, bar)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Vec<u32>> {
+        vec![
+            vec![1000, 2000, 3000],
+            vec![4000],
+            vec![5000, 6000],
+            vec![7000, 8000, 9000],
+            vec![10000],
+        ]
+    }
+
+    #[test]
+    fn top_k_tests() {
+        assert_eq!(top_k(totals(example()), 1), vec![24000]);
+        assert_eq!(top_k(totals(example()), 3), vec![24000, 11000, 10000]);
+        assert_eq!(top_k(totals(example()), 10).len(), 5);
+        assert!(top_k(totals(example()), 0).is_empty());
+        assert_eq!(top_k(totals(example()), usize::MAX).len(), 5);
+    }
+
+    #[test]
+    fn streamed_totals() {
+        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+        let streamed = read_totals(input.as_bytes())
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(streamed, totals(example()).collect::<Vec<_>>());
+
+        let large = format!("{}\n{}\n", u32::MAX, u32::MAX);
+        let streamed = read_totals(large.as_bytes()).collect::<Result<Vec<_>>>();
+        assert_eq!(streamed.unwrap(), vec![2 * u32::MAX as u64]);
+
+        let error = read_totals("1\n\nx\n".as_bytes())
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Line 3: invalid digit found in string");
+    }
+
+    #[test]
+    fn large_median() {
+        let stats = ElfStats::new(1, vec![u32::MAX, u32::MAX]);
+        assert_eq!(stats.total, 2 * u32::MAX as u64);
+        assert_eq!(stats.median, u32::MAX as f64);
+    }
+
+    #[test]
+    fn elf_stats() {
+        let report = Report::new(example(), 2);
+        assert_eq!(
+            report.elves[0],
+            ElfStats {
+                elf: 1,
+                count: 3,
+                total: 6000,
+                mean: 2000.0,
+                median: 2000.0,
+            }
+        );
+        assert_eq!(report.elves[2].median, 5500.0);
+    }
+
+    #[test]
+    fn distribution() {
+        let report = Report::new(example(), 2);
+        assert_eq!(report.percentile(50), Some(10000));
+        assert_eq!(report.percentile(100), Some(24000));
+        assert_eq!(report.percentile(0), Some(4000));
+        assert_eq!(report.histogram(), vec![(4000, 4), (14000, 1)]);
+    }
+
+    #[test]
+    fn csv() {
+        let report = Report::new(vec![vec![1, 2]], 1);
+        assert_eq!(
+            report.to_csv(),
+            "elf,count,total,mean,median\n1,2,3,1.50,1.5\n"
+        );
+    }
+}
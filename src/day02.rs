@@ -0,0 +1,342 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+pub mod analysis;
+
+/// Index of a move in the cyclic order of its `Rules`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lost,
+    Tied,
+    Won,
+}
+
+/// How the second column of the strategy guide is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    /// The second column is the move to play (part 1).
+    MyMove,
+    /// The second column is the outcome to aim for (part 2).
+    Outcome,
+}
+
+/// A lookup from symbols, each of which has to be different.
+fn unique_symbols<'a, T>(
+    symbols: impl IntoIterator<Item = (&'a str, T)>,
+) -> Result<HashMap<String, T>> {
+    let mut table = HashMap::new();
+    for (symbol, value) in symbols {
+        if table.insert(symbol.to_string(), value).is_some() {
+            Err(anyhow!("Symbol {:?} is used more than once", symbol))?;
+        }
+    }
+    Ok(table)
+}
+
+/// The most moves default symbols can be made for, one letter each.
+pub const MAX_MOVES: usize = 26;
+
+/// A cyclic game with an odd number of moves, each move beating the half of
+/// the others that come right before it in the cycle. With three moves that is
+/// rock, paper, scissors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub names: Vec<String>,
+    pub move_scores: Vec<u32>,
+    /// Scores for losing, tying and winning.
+    pub outcome_scores: [u32; 3],
+    pub their_symbols: HashMap<String, Move>,
+    pub my_symbols: HashMap<String, Move>,
+    pub outcome_symbols: HashMap<String, Outcome>,
+}
+
+impl Rules {
+    /// Moves are scored 1, 2, 3, ... in cycle order and outcomes 0, 3 and 6.
+    /// Their moves are read as `A`, `B`, `C`, ... mine as `X`, `Y`, `Z`, `A`,
+    /// ... wrapping round the alphabet, and outcomes as `X`, `Y` and `Z`. There
+    /// can be at most `MAX_MOVES` moves.
+    pub fn new(names: &[&str]) -> Result<Self> {
+        if names.len() < 3 || names.len().is_multiple_of(2) {
+            Err(anyhow!(
+                "A cyclic game needs an odd number of moves, not {}",
+                names.len()
+            ))?;
+        }
+        if names.len() > MAX_MOVES {
+            Err(anyhow!(
+                "A game can have at most {} moves, not {}",
+                MAX_MOVES,
+                names.len()
+            ))?;
+        }
+
+        let symbols = |first: u8| {
+            (0..names.len())
+                .map(|idx| {
+                    let letter = b'A' + (((first - b'A') as usize + idx) % 26) as u8;
+                    ((letter as char).to_string(), Move(idx))
+                })
+                .collect()
+        };
+
+        Ok(Rules {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            move_scores: (1..=names.len() as u32).collect(),
+            outcome_scores: [0, 3, 6],
+            their_symbols: symbols(b'A'),
+            my_symbols: symbols(b'X'),
+            outcome_symbols: HashMap::from([
+                ("X".to_string(), Outcome::Lost),
+                ("Y".to_string(), Outcome::Tied),
+                ("Z".to_string(), Outcome::Won),
+            ]),
+        })
+    }
+
+    pub fn rock_paper_scissors() -> Self {
+        Rules::new(&["Rock", "Paper", "Scissors"]).unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> Self {
+        Rules::new(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]).unwrap()
+    }
+
+    pub fn with_their_symbols(self, symbols: &[&str]) -> Result<Self> {
+        Ok(Rules {
+            their_symbols: self.symbol_table(symbols)?,
+            ..self
+        })
+    }
+
+    pub fn with_my_symbols(self, symbols: &[&str]) -> Result<Self> {
+        Ok(Rules {
+            my_symbols: self.symbol_table(symbols)?,
+            ..self
+        })
+    }
+
+    /// Symbols for losing, tying and winning.
+    pub fn with_outcome_symbols(self, symbols: [&str; 3]) -> Result<Self> {
+        let outcomes = [Outcome::Lost, Outcome::Tied, Outcome::Won];
+        Ok(Rules {
+            outcome_symbols: unique_symbols(symbols.iter().copied().zip(outcomes))?,
+            ..self
+        })
+    }
+
+    pub fn with_move_scores(self, move_scores: &[u32]) -> Result<Self> {
+        if move_scores.len() != self.len() {
+            Err(anyhow!(
+                "Expected {} move scores, got {}",
+                self.len(),
+                move_scores.len()
+            ))?;
+        }
+        Ok(Rules {
+            move_scores: move_scores.to_vec(),
+            ..self
+        })
+    }
+
+    pub fn with_outcome_scores(self, outcome_scores: [u32; 3]) -> Self {
+        Rules {
+            outcome_scores,
+            ..self
+        }
+    }
+
+    fn symbol_table(&self, symbols: &[&str]) -> Result<HashMap<String, Move>> {
+        if symbols.len() != self.len() {
+            Err(anyhow!(
+                "Expected {} symbols, got {}",
+                self.len(),
+                symbols.len()
+            ))?;
+        }
+        unique_symbols(
+            symbols
+                .iter()
+                .enumerate()
+                .map(|(idx, symbol)| (*symbol, Move(idx))),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = Move> {
+        (0..self.len()).map(Move)
+    }
+
+    pub fn outcome(&self, mine: Move, theirs: Move) -> Outcome {
+        let ahead = (mine.0 + self.len() - theirs.0) % self.len();
+        if ahead == 0 {
+            Outcome::Tied
+        } else if ahead <= self.len() / 2 {
+            Outcome::Won
+        } else {
+            Outcome::Lost
+        }
+    }
+
+    /// The move that gets `outcome` against `theirs`. When several do, as with
+    /// more than three moves, the best scoring one is picked.
+    pub fn move_for(&self, theirs: Move, outcome: Outcome) -> Move {
+        self.moves()
+            .filter(|mine| self.outcome(*mine, theirs) == outcome)
+            .max_by_key(|mine| self.move_scores[mine.0])
+            .unwrap()
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Lost => self.outcome_scores[0],
+            Outcome::Tied => self.outcome_scores[1],
+            Outcome::Won => self.outcome_scores[2],
+        }
+    }
+
+    /// Parses a line of the strategy guide, e.g. `A Y`.
+    pub fn game(&self, line: &str, reading: Reading) -> Result<Game> {
+        let (theirs, second) = line
+            .split_once(' ')
+            .ok_or(anyhow!("Line missing space delimiter!"))?;
+
+        let theirs = *self
+            .their_symbols
+            .get(theirs)
+            .ok_or(anyhow!("Unknown move for them: {:?}", theirs))?;
+
+        let mine = match reading {
+            Reading::MyMove => *self
+                .my_symbols
+                .get(second)
+                .ok_or(anyhow!("Unknown move for me: {:?}", second))?,
+            Reading::Outcome => {
+                let outcome = self
+                    .outcome_symbols
+                    .get(second)
+                    .ok_or(anyhow!("Unknown outcome: {:?}", second))?;
+                self.move_for(theirs, *outcome)
+            }
+        };
+
+        Ok(Game { theirs, mine })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Game {
+    pub theirs: Move,
+    pub mine: Move,
+}
+
+impl Game {
+    pub fn score(&self, rules: &Rules) -> u32 {
+        let my_score = rules.move_scores[self.mine.0];
+        let win_score = rules.outcome_score(rules.outcome(self.mine, self.theirs));
+
+        win_score + my_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rock_paper_scissors() {
+        let rules = Rules::rock_paper_scissors();
+        let [rock, paper, scissors] = [Move(0), Move(1), Move(2)];
+
+        assert_eq!(rules.outcome(paper, rock), Outcome::Won);
+        assert_eq!(rules.outcome(rock, scissors), Outcome::Won);
+        assert_eq!(rules.outcome(scissors, rock), Outcome::Lost);
+        assert_eq!(rules.outcome(paper, paper), Outcome::Tied);
+        assert_eq!(rules.move_for(rock, Outcome::Lost), scissors);
+    }
+
+    #[test]
+    fn lizard_spock() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let names = |mine: Move, theirs: Move| {
+            (rules.names[mine.0].as_str(), rules.names[theirs.0].as_str())
+        };
+
+        let wins = rules
+            .moves()
+            .flat_map(|mine| rules.moves().map(move |theirs| (mine, theirs)))
+            .filter(|(mine, theirs)| rules.outcome(*mine, *theirs) == Outcome::Won)
+            .map(|(mine, theirs)| names(mine, theirs))
+            .collect::<Vec<_>>();
+
+        assert_eq!(wins.len(), 10);
+        assert_eq!(rules.my_symbols["A"], Move(3));
+        assert_eq!(rules.my_symbols["B"], Move(4));
+        for win in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            assert!(wins.contains(&win), "{:?}", win);
+        }
+    }
+
+    #[test]
+    fn custom_symbols() {
+        let rules = Rules::rock_paper_scissors_lizard_spock()
+            .with_their_symbols(&["r", "k", "p", "l", "s"])
+            .unwrap()
+            .with_my_symbols(&["R", "K", "P", "L", "S"])
+            .unwrap()
+            .with_outcome_symbols(["lose", "draw", "win"])
+            .unwrap()
+            .with_outcome_scores([0, 1, 2]);
+
+        // Paper (3) covers rock.
+        assert_eq!(
+            rules.game("r P", Reading::MyMove).unwrap().score(&rules),
+            2 + 3
+        );
+        // Spock (2) and paper (3) both beat rock, paper scores more.
+        let game = rules.game("r win", Reading::Outcome).unwrap();
+        assert_eq!(game.mine, Move(2));
+        assert!(rules.game("x P", Reading::MyMove).is_err());
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert!(Rules::new(&["Rock", "Paper"]).is_err());
+        assert!(Rules::new(&["a", "b", "c", "d"]).is_err());
+        assert!(Rules::new(&["move"; MAX_MOVES + 1]).is_err());
+        assert_eq!(
+            Rules::rock_paper_scissors()
+                .with_my_symbols(&["R", "P", "R"])
+                .unwrap_err()
+                .to_string(),
+            "Symbol \"R\" is used more than once"
+        );
+        assert!(Rules::rock_paper_scissors()
+            .with_outcome_symbols(["l", "w", "w"])
+            .is_err());
+        assert!(Rules::rock_paper_scissors()
+            .with_move_scores(&[1, 2])
+            .is_err());
+    }
+}
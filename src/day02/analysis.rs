@@ -0,0 +1,304 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+
+use super::{Game, Move, Reading, Rules};
+use crate::collect_lines;
+
+const EPSILON: f64 = 1e-9;
+
+/// The payoff matrix of a ruleset, `payoffs[mine][theirs]` being my score from
+/// `Game::score`, treated as a zero-sum game against an opponent who wants my
+/// score as low as possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub payoffs: Vec<Vec<f64>>,
+    /// My optimal mixed strategy, the probability of playing each move.
+    pub equilibrium: Vec<f64>,
+    /// My expected score per round when both sides play optimally.
+    pub value: f64,
+}
+
+/// Who the strategy guide is played against.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Opponent {
+    /// Plays each move with the given probabilities, whatever the guide says.
+    Random(Vec<f64>),
+    /// Learns how often the guide plays each move and always plays the best
+    /// response to that mix.
+    Adaptive,
+}
+
+impl Opponent {
+    pub fn uniform(rules: &Rules) -> Self {
+        Opponent::Random(vec![1.0 / rules.len() as f64; rules.len()])
+    }
+}
+
+/// Expected scores of following a strategy guide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    pub rounds: usize,
+    /// The score if the opponent plays exactly as the guide predicts.
+    pub as_written: u32,
+    pub against_uniform: f64,
+    pub against_adaptive: f64,
+    /// What optimal play would be expected to score over as many rounds.
+    pub equilibrium: f64,
+}
+
+impl Analysis {
+    pub fn new(rules: &Rules) -> Self {
+        let payoffs = rules
+            .moves()
+            .map(|mine| {
+                rules
+                    .moves()
+                    .map(|theirs| Game { mine, theirs }.score(rules) as f64)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let (equilibrium, value) = solve_zero_sum(&payoffs);
+
+        Self {
+            payoffs,
+            equilibrium,
+            value,
+        }
+    }
+
+    /// My expected score per round playing `mine` against `theirs`, both
+    /// being probabilities over the moves.
+    pub fn expected(&self, mine: &[f64], theirs: &[f64]) -> f64 {
+        self.payoffs
+            .iter()
+            .zip(mine)
+            .map(|(row, p)| p * row.iter().zip(theirs).map(|(s, q)| s * q).sum::<f64>())
+            .sum()
+    }
+
+    /// My expected score per round playing `mine` against `opponent`.
+    pub fn against(&self, mine: &[f64], opponent: &Opponent) -> f64 {
+        match opponent {
+            Opponent::Random(theirs) => self.expected(mine, theirs),
+            Opponent::Adaptive => (0..self.payoffs.len())
+                .map(|theirs| {
+                    let mut pure = vec![0.0; self.payoffs.len()];
+                    pure[theirs] = 1.0;
+                    self.expected(mine, &pure)
+                })
+                .fold(f64::INFINITY, f64::min),
+        }
+    }
+
+    /// Plays the moves of a strategy guide, each line read as in the puzzle.
+    pub fn evaluate(
+        &self,
+        rules: &Rules,
+        lines: &[String],
+        reading: Reading,
+    ) -> Result<Evaluation> {
+        let games = lines
+            .iter()
+            .map(|line| rules.game(line, reading))
+            .collect::<Result<Vec<_>>>()?;
+
+        let rounds = games.len();
+        let mut mix = vec![0.0; rules.len()];
+        for game in &games {
+            mix[game.mine.0] += 1.0 / rounds as f64;
+        }
+
+        Ok(Evaluation {
+            rounds,
+            as_written: games.iter().map(|game| game.score(rules)).sum(),
+            against_uniform: self.against(&mix, &Opponent::uniform(rules)) * rounds as f64,
+            against_adaptive: self.against(&mix, &Opponent::Adaptive) * rounds as f64,
+            equilibrium: self.value * rounds as f64,
+        })
+    }
+
+    /// The opponent's best replies to `mine`.
+    pub fn best_responses(&self, mine: Move) -> Vec<Move> {
+        let row = &self.payoffs[mine.0];
+        let worst = row.iter().copied().fold(f64::INFINITY, f64::min);
+        (0..row.len())
+            .filter(|theirs| row[*theirs] - worst < EPSILON)
+            .map(Move)
+            .collect()
+    }
+}
+
+/// Prints the equilibrium of `rules` and how each strategy guide file fares.
+pub fn compare(rules: &Rules, guides: &[String], reading: Reading) -> Result<()> {
+    let analysis = Analysis::new(rules);
+
+    println!("equilibrium value per round: {:.3}", analysis.value);
+    for (name, p) in rules.names.iter().zip(&analysis.equilibrium) {
+        println!("  {:<10} {:.3}", name, p);
+    }
+
+    for guide in guides {
+        let lines = collect_lines::<String>(guide)?;
+        println!("\n{}", guide);
+        println!("{}", analysis.evaluate(rules, &lines, reading)?);
+    }
+
+    Ok(())
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "rounds:           {}", self.rounds)?;
+        writeln!(f, "as written:       {}", self.as_written)?;
+        writeln!(f, "against uniform:  {:.1}", self.against_uniform)?;
+        writeln!(f, "against adaptive: {:.1}", self.against_adaptive)?;
+        write!(f, "equilibrium:      {:.1}", self.equilibrium)
+    }
+}
+
+/// Solves the zero-sum game where the row player maximizes `payoffs`, returning
+/// their optimal mixed strategy and the value of the game.
+///
+/// After shifting the payoffs to be positive this is the linear program
+/// `max sum(w) st payoffs * w <= 1, w >= 0` for the column player, solved with
+/// the simplex method. The row player's strategy is read off the dual values.
+pub fn solve_zero_sum(payoffs: &[Vec<f64>]) -> (Vec<f64>, f64) {
+    let rows = payoffs.len();
+    let cols = payoffs[0].len();
+    let min = payoffs
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::INFINITY, f64::min);
+    let shift = 1.0 - min;
+
+    // Constraint rows followed by the objective row. The columns are the
+    // column player's variables, one slack per row, then the right hand side.
+    let width = cols + rows + 1;
+    let mut tableau = vec![vec![0.0; width]; rows + 1];
+    for (row, payoff) in payoffs.iter().enumerate() {
+        for (cell, p) in tableau[row].iter_mut().zip(payoff) {
+            *cell = p + shift;
+        }
+        tableau[row][cols + row] = 1.0;
+        tableau[row][width - 1] = 1.0;
+    }
+    tableau[rows][..cols].fill(-1.0);
+    let mut basis = (cols..cols + rows).collect::<Vec<_>>();
+
+    // Bland's rule: smallest entering and leaving indices, so no cycling.
+    while let Some(entering) = (0..width - 1).find(|&col| tableau[rows][col] < -EPSILON) {
+        let leaving = (0..rows)
+            .filter(|&row| tableau[row][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let ratio = |row: usize| tableau[row][width - 1] / tableau[row][entering];
+                ratio(a)
+                    .partial_cmp(&ratio(b))
+                    .unwrap()
+                    .then(basis[a].cmp(&basis[b]))
+            })
+            .expect("positive payoffs keep the program bounded");
+
+        let pivot = tableau[leaving][entering];
+        tableau[leaving].iter_mut().for_each(|x| *x /= pivot);
+        let pivot_row = tableau[leaving].clone();
+        for (row, cells) in tableau.iter_mut().enumerate() {
+            if row != leaving {
+                let factor = cells[entering];
+                for (cell, p) in cells.iter_mut().zip(&pivot_row) {
+                    *cell -= factor * p;
+                }
+            }
+        }
+        basis[leaving] = entering;
+    }
+
+    let total = tableau[rows][width - 1];
+    let strategy = (0..rows)
+        .map(|row| tableau[rows][cols + row] / total)
+        .collect();
+
+    (strategy, 1.0 / total - shift)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn matching_pennies() {
+        let (strategy, value) = solve_zero_sum(&[vec![1.0, -1.0], vec![-1.0, 1.0]]);
+        assert_close(strategy[0], 0.5);
+        assert_close(strategy[1], 0.5);
+        assert_close(value, 0.0);
+    }
+
+    #[test]
+    fn uneven_equilibrium() {
+        let (strategy, value) = solve_zero_sum(&[vec![2.0, -1.0], vec![-1.0, 1.0]]);
+        assert_close(strategy[0], 0.4);
+        assert_close(strategy[1], 0.6);
+        assert_close(value, 0.2);
+    }
+
+    #[test]
+    fn dominated_move() {
+        // The second row is never worth playing.
+        let (strategy, value) = solve_zero_sum(&[vec![3.0, 2.0], vec![1.0, 0.0]]);
+        assert_close(strategy[0], 1.0);
+        assert_close(value, 2.0);
+    }
+
+    #[test]
+    fn rock_paper_scissors() {
+        let rules = Rules::rock_paper_scissors();
+        let analysis = Analysis::new(&rules);
+
+        assert_eq!(
+            analysis.payoffs,
+            vec![
+                vec![4.0, 1.0, 7.0],
+                vec![8.0, 5.0, 2.0],
+                vec![3.0, 9.0, 6.0],
+            ]
+        );
+        assert_close(analysis.equilibrium.iter().sum(), 1.0);
+
+        // No reply to the equilibrium does better than the value.
+        for theirs in 0..3 {
+            let mut pure = vec![0.0; 3];
+            pure[theirs] = 1.0;
+            assert!(analysis.expected(&analysis.equilibrium, &pure) >= analysis.value - 1e-6);
+        }
+        assert_close(
+            analysis.against(&analysis.equilibrium, &Opponent::Adaptive),
+            analysis.value,
+        );
+        assert_eq!(analysis.best_responses(Move(0)), vec![Move(1)]);
+    }
+
+    #[test]
+    fn evaluate_guide() {
+        let rules = Rules::rock_paper_scissors();
+        let analysis = Analysis::new(&rules);
+        let lines = ["A Y", "B X", "C Z"].map(String::from);
+
+        let evaluation = analysis.evaluate(&rules, &lines, Reading::MyMove).unwrap();
+        assert_eq!(evaluation.as_written, 15);
+        // One each of rock, paper and scissors is uniform play.
+        assert_close(evaluation.against_uniform, 3.0 * 5.0);
+        assert_close(evaluation.against_adaptive, 3.0 * 5.0);
+
+        let lines = ["A Y", "B Y"].map(String::from);
+        let evaluation = analysis.evaluate(&rules, &lines, Reading::MyMove).unwrap();
+        // Always paper, so the adaptive opponent always plays scissors.
+        assert_close(evaluation.against_adaptive, 2.0 * 2.0);
+        assert_close(evaluation.against_uniform, 2.0 * 5.0);
+    }
+}
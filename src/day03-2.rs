@@ -1,72 +1,38 @@
-use std::collections::HashSet;
-use std::str::FromStr;
-
-use anyhow::anyhow;
-use anyhow::Error;
 use anyhow::Result;
 
 use aoc::collect_lines;
+use aoc::day03::badges;
+use aoc::day03::RuckSack;
+use aoc::input_file;
 use aoc::time_it;
 
+const GROUP_SIZE: usize = 3;
+
 fn main() -> Result<()> {
     time_it(|| solution())?;
     Ok(())
 }
 
 fn solution() -> Result<u32> {
-    let sacks = collect_lines::<RuckSack>("input/day03.txt")?;
-    Ok(sacks
-        .chunks(3)
-        .map(|sacks| chr_to_priority(get_common(&sacks[0], &sacks[1], &sacks[2]).expect("what?")))
-        .sum())
-}
-
-fn chr_to_priority(c: char) -> u32 {
-    if c.is_lowercase() {
-        (c as u32) + 1 - ('a' as u32)
-    } else {
-        (c as u32) + 27 - ('A' as u32)
-    }
-}
-
-#[derive(Debug)]
-struct RuckSack {
-    cmp: HashSet<char>,
-}
-
-fn get_common(sack1: &RuckSack, sack2: &RuckSack, sack3: &RuckSack) -> Result<char> {
-    Ok(sack1
-        .cmp
-        .intersection(&sack2.cmp)
-        .find(|c| sack3.cmp.contains(c))
-        .ok_or(anyhow!("No matching item!"))?
-        .to_owned())
-}
-
-impl FromStr for RuckSack {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(RuckSack {
-            cmp: HashSet::from_iter(s.chars()),
-        })
-    }
+    let sacks = collect_lines::<RuckSack>(input_file(3)?)?;
+    Ok(badges(&sacks, GROUP_SIZE)?.into_iter().sum())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aoc::day03::priority;
 
     #[test]
     fn chr_conv() {
-        assert_eq!(chr_to_priority('a'), 1);
-        assert_eq!(chr_to_priority('b'), 2);
-        assert_eq!(chr_to_priority('c'), 3);
-        assert_eq!(chr_to_priority('z'), 26);
-        assert_eq!(chr_to_priority('A'), 27);
-        assert_eq!(chr_to_priority('B'), 28);
-        assert_eq!(chr_to_priority('C'), 29);
-        assert_eq!(chr_to_priority('Z'), 52);
+        assert_eq!(priority('a').unwrap(), 1);
+        assert_eq!(priority('b').unwrap(), 2);
+        assert_eq!(priority('c').unwrap(), 3);
+        assert_eq!(priority('z').unwrap(), 26);
+        assert_eq!(priority('A').unwrap(), 27);
+        assert_eq!(priority('B').unwrap(), 28);
+        assert_eq!(priority('C').unwrap(), 29);
+        assert_eq!(priority('Z').unwrap(), 52);
     }
 
     #[test]
@@ -0,0 +1,216 @@
+use std::fmt::Display;
+use std::ops::BitAnd;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use itertools::Itertools;
+
+/// A set of items stored as a bitmask, bit `p` being set for the item with
+/// priority `p`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Items(pub u64);
+
+pub fn priority(c: char) -> Result<u32> {
+    match c {
+        'a'..='z' => Ok(c as u32 + 1 - 'a' as u32),
+        'A'..='Z' => Ok(c as u32 + 27 - 'A' as u32),
+        _ => Err(anyhow!("Not an item: {:?}", c)),
+    }
+}
+
+pub fn item(priority: u32) -> Result<char> {
+    match priority {
+        1..=26 => Ok(char::from_u32('a' as u32 + priority - 1).unwrap()),
+        27..=52 => Ok(char::from_u32('A' as u32 + priority - 27).unwrap()),
+        _ => Err(anyhow!("No item has priority {}", priority)),
+    }
+}
+
+impl Items {
+    /// Items in every one of `sets`.
+    pub fn common<'a, I>(sets: I) -> Items
+    where
+        I: IntoIterator<Item = &'a Items>,
+    {
+        sets.into_iter()
+            .copied()
+            .reduce(|a, b| a & b)
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn priorities(&self) -> impl Iterator<Item = u32> + '_ {
+        (1..=52).filter(|p| self.0 & (1 << p) != 0)
+    }
+
+    /// The priority of the only item in the set.
+    pub fn single(&self) -> Result<u32> {
+        match self.len() {
+            1 => Ok(self.0.trailing_zeros()),
+            0 => Err(anyhow!("no common item")),
+            _ => Err(anyhow!("several common items: {}", self)),
+        }
+    }
+}
+
+impl BitAnd for Items {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        Items(self.0 & other.0)
+    }
+}
+
+impl FromStr for Items {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(priority)
+            .fold_ok(Items::default(), |items, p| Items(items.0 | 1 << p))
+    }
+}
+
+impl Display for Items {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.priorities().flat_map(item).join(", "))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuckSack {
+    pub compartments: [Items; 2],
+}
+
+impl RuckSack {
+    pub fn items(&self) -> Items {
+        Items(self.compartments[0].0 | self.compartments[1].0)
+    }
+
+    /// Priority of the item packed in both compartments.
+    pub fn misplaced(&self) -> Result<u32> {
+        Items::common(&self.compartments).single()
+    }
+}
+
+impl FromStr for RuckSack {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() {
+            Err(anyhow!("Not all items in {:?} are letters", s))?;
+        }
+        if !s.len().is_multiple_of(2) {
+            Err(anyhow!("Odd number of items, can't split {:?} in two", s))?;
+        }
+        let (cmp1, cmp2) = s.split_at(s.len() / 2);
+
+        Ok(RuckSack {
+            compartments: [cmp1.parse()?, cmp2.parse()?],
+        })
+    }
+}
+
+/// Priority of the badge of each group of `width` consecutive sacks.
+pub fn badges(sacks: &[RuckSack], width: usize) -> Result<Vec<u32>> {
+    if width == 0 || !sacks.len().is_multiple_of(width) {
+        Err(anyhow!(
+            "{} sacks can't be split into groups of {}",
+            sacks.len(),
+            width
+        ))?;
+    }
+
+    sacks
+        .chunks(width)
+        .enumerate()
+        .map(|(group, sacks)| {
+            let items = sacks.iter().map(|sack| sack.items()).collect_vec();
+            Items::common(&items).single().map_err(|e| {
+                anyhow!(
+                    "Group {} (lines {}-{}) has {}",
+                    group + 1,
+                    group * width + 1,
+                    (group + 1) * width,
+                    e
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 6] = [
+        "vJrwpWtwJgWrhcsFMMfFFhFp",
+        "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+        "PmmdzqPrVvPwwTWBwg",
+        "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+        "ttgJtRGJQctTZtZT",
+        "CrZsJsPPZsGzwwsLwLmpwMDw",
+    ];
+
+    fn example() -> Vec<RuckSack> {
+        EXAMPLE.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn priorities() {
+        assert_eq!(priority('a').unwrap(), 1);
+        assert_eq!(priority('Z').unwrap(), 52);
+        assert!(priority('1').is_err());
+        for p in 1..=52 {
+            assert_eq!(priority(item(p).unwrap()).unwrap(), p);
+        }
+        assert!(item(0).is_err());
+        assert!(item(53).is_err());
+    }
+
+    #[test]
+    fn items() {
+        let items = "abcab".parse::<Items>().unwrap();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items.to_string(), "a, b, c");
+        assert_eq!(
+            Items::common(&["abZ".parse().unwrap(), "bZc".parse().unwrap()]).to_string(),
+            "b, Z"
+        );
+    }
+
+    #[test]
+    fn misplaced() {
+        let misplaced = example()
+            .iter()
+            .map(|sack| item(sack.misplaced().unwrap()).unwrap())
+            .collect::<String>();
+        assert_eq!(misplaced, "pLPvts");
+        assert!("abcd".parse::<RuckSack>().unwrap().misplaced().is_err());
+        assert!("abc".parse::<RuckSack>().is_err());
+        assert!("aéb".parse::<RuckSack>().is_err());
+    }
+
+    #[test]
+    fn groups() {
+        assert_eq!(badges(&example(), 3).unwrap(), vec![18, 52]);
+        assert_eq!(
+            badges(&example(), 6).unwrap_err().to_string(),
+            "Group 1 (lines 1-6) has no common item"
+        );
+        assert!(badges(&example(), 4).is_err());
+
+        let sacks = ["abab", "abab"].map(|s| s.parse::<RuckSack>().unwrap());
+        assert_eq!(
+            badges(&sacks, 2).unwrap_err().to_string(),
+            "Group 1 (lines 1-2) has several common items: a, b"
+        );
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use itertools::Itertools;
+
+/// The section assignments of the two elves on a line of the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair {
+    pub r1: Range<u32>,
+    pub r2: Range<u32>,
+}
+
+impl Pair {
+    /// Whether one of the ranges fully contains the other.
+    pub fn fully_contains(&self) -> bool {
+        contains(&self.r1, &self.r2) || contains(&self.r2, &self.r1)
+    }
+
+    pub fn overlaps(&self) -> bool {
+        self.r1.start < self.r2.end && self.r2.start < self.r1.end
+    }
+}
+
+fn contains(outer: &Range<u32>, inner: &Range<u32>) -> bool {
+    outer.start <= inner.start && outer.end >= inner.end
+}
+
+impl FromStr for Pair {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (r1, r2) = s
+            .trim()
+            .split_once(',')
+            .ok_or(anyhow!("Pair does not contain a comma!"))?;
+        Ok(Pair {
+            r1: parse_range(r1)?,
+            r2: parse_range(r2)?,
+        })
+    }
+}
+
+fn parse_range(s: &str) -> Result<Range<u32>> {
+    let (start, end) = s
+        .split_once('-')
+        .ok_or(anyhow!("Range does not contain a dash!"))?;
+    let (start, end) = (start.parse::<u32>()?, end.parse::<u32>()?);
+    if end < start {
+        Err(anyhow!("Range {:?} ends before it starts!", s))?;
+    }
+    let end =
+        end.checked_add(1)
+            .ok_or(anyhow!("Range {:?} ends past section {}", s, u32::MAX - 1))?;
+    Ok(start..end)
+}
+
+/// The sections of a single elf, elves being numbered from 1 in the order they
+/// appear in the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub elf: usize,
+    pub sections: Range<u32>,
+}
+
+pub fn assignments(pairs: &[Pair]) -> Vec<Assignment> {
+    pairs
+        .iter()
+        .flat_map(|pair| [pair.r1.clone(), pair.r2.clone()])
+        .enumerate()
+        .map(|(idx, sections)| Assignment {
+            elf: idx + 1,
+            sections,
+        })
+        .collect()
+}
+
+/// Two elves with overlapping sections, `first` being the lower numbered one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Overlap {
+    pub first: usize,
+    pub second: usize,
+    /// Whether the sections of one elf contain those of the other.
+    pub contains: bool,
+}
+
+/// Every pair of elves whose sections overlap.
+///
+/// Sweeps the assignments by start section, keeping those still running
+/// ordered by end, so this is O(n log n) plus the number of overlaps found.
+pub fn overlaps(assignments: &[Assignment]) -> Vec<Overlap> {
+    let sorted = assignments
+        .iter()
+        .sorted_by_key(|a| a.sections.start)
+        .collect_vec();
+
+    let mut active = BTreeSet::new();
+    let mut overlaps = Vec::new();
+    for (idx, a) in sorted.iter().enumerate() {
+        while let Some(&(end, _)) = active.first() {
+            if end > a.sections.start {
+                break;
+            }
+            active.pop_first();
+        }
+
+        for &(_, other) in &active {
+            let b: &Assignment = sorted[other];
+            overlaps.push(Overlap {
+                first: a.elf.min(b.elf),
+                second: a.elf.max(b.elf),
+                contains: contains(&a.sections, &b.sections) || contains(&b.sections, &a.sections),
+            });
+        }
+        active.insert((a.sections.end, idx));
+    }
+
+    overlaps.sort();
+    overlaps
+}
+
+/// The sections covered by more than `k` elves, as runs of sections covered by
+/// the same number of elves along with that number.
+pub fn covered_by_more_than(assignments: &[Assignment], k: usize) -> Vec<(Range<u32>, usize)> {
+    let events = assignments
+        .iter()
+        .flat_map(|a| [(a.sections.start, 1), (a.sections.end, -1)])
+        .sorted()
+        .collect_vec();
+
+    let mut runs: Vec<(Range<u32>, usize)> = Vec::new();
+    let mut depth = 0isize;
+    let mut cursor = 0;
+    for (section, delta) in events {
+        if section > cursor && depth > k as isize {
+            match runs.last_mut() {
+                Some((run, d)) if run.end == cursor && *d == depth as usize => run.end = section,
+                _ => runs.push((cursor..section, depth as usize)),
+            }
+        }
+        depth += delta;
+        cursor = section;
+    }
+
+    runs
+}
+
+/// The fewest elves whose sections cover every section covered by any elf.
+///
+/// Within each contiguous stretch of covered sections this greedily picks,
+/// among the elves starting inside what is covered so far, the one reaching
+/// furthest.
+pub fn minimum_cover(assignments: &[Assignment]) -> Vec<usize> {
+    let sorted = assignments
+        .iter()
+        .sorted_by_key(|a| a.sections.start)
+        .collect_vec();
+
+    let mut cover = Vec::new();
+    let mut idx = 0;
+    while idx < sorted.len() {
+        let mut reach = sorted[idx].sections.start;
+        loop {
+            let mut best: Option<&Assignment> = None;
+            while idx < sorted.len() && sorted[idx].sections.start <= reach {
+                if best.is_none_or(|b| sorted[idx].sections.end > b.sections.end) {
+                    best = Some(sorted[idx]);
+                }
+                idx += 1;
+            }
+            match best {
+                Some(b) if b.sections.end > reach => {
+                    cover.push(b.elf);
+                    reach = b.sections.end;
+                }
+                _ => break,
+            }
+        }
+    }
+
+    cover.sort();
+    cover
+}
+
+/// Overlaps, crowded sections and minimum cover of all the assignments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sweep {
+    pub overlaps: Vec<Overlap>,
+    pub k: usize,
+    pub crowded: Vec<(Range<u32>, usize)>,
+    pub cover: Vec<usize>,
+}
+
+impl Sweep {
+    pub fn new(pairs: &[Pair], k: usize) -> Self {
+        let assignments = assignments(pairs);
+
+        Self {
+            overlaps: overlaps(&assignments),
+            k,
+            crowded: covered_by_more_than(&assignments, k),
+            cover: minimum_cover(&assignments),
+        }
+    }
+}
+
+impl Display for Sweep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let containing = self.overlaps.iter().filter(|o| o.contains).count();
+        writeln!(
+            f,
+            "{} overlapping pairs, {} of them containing",
+            self.overlaps.len(),
+            containing
+        )?;
+        for o in &self.overlaps {
+            let relation = if o.contains { "nested" } else { "overlap" };
+            writeln!(f, "  {:>5} {:>5}  {}", o.first, o.second, relation)?;
+        }
+
+        writeln!(f, "\nsections covered by more than {} elves", self.k)?;
+        for (run, depth) in &self.crowded {
+            writeln!(f, "  {:>5}-{:<5} {}", run.start, run.end - 1, depth)?;
+        }
+
+        writeln!(f, "\n{} elves cover every section", self.cover.len())?;
+        write!(f, "  {}", self.cover.iter().join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<Pair> {
+        [
+            "2-4,6-8", "2-3,4-5", "5-7,7-9", "2-8,3-7", "6-6,4-6", "2-6,4-8",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect()
+    }
+
+    fn brute_force(assignments: &[Assignment]) -> Vec<Overlap> {
+        assignments
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| {
+                Pair {
+                    r1: a.sections.clone(),
+                    r2: b.sections.clone(),
+                }
+                .overlaps()
+            })
+            .map(|(a, b)| Overlap {
+                first: a.elf,
+                second: b.elf,
+                contains: contains(&a.sections, &b.sections) || contains(&b.sections, &a.sections),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pairs() {
+        let pairs = example();
+        assert_eq!(pairs.iter().filter(|p| p.fully_contains()).count(), 2);
+        assert_eq!(pairs.iter().filter(|p| p.overlaps()).count(), 4);
+        assert!("4-2,1-1".parse::<Pair>().is_err());
+        assert!("1-4294967295,1-1".parse::<Pair>().is_err());
+        assert!("1-4294967294,1-1".parse::<Pair>().is_ok());
+    }
+
+    #[test]
+    fn all_overlaps() {
+        let assignments = assignments(&example());
+        let overlaps = overlaps(&assignments);
+        assert_eq!(overlaps, brute_force(&assignments));
+        assert!(overlaps.contains(&Overlap {
+            first: 7,
+            second: 8,
+            contains: true
+        }));
+        // The elves of lines 3 and 4 share section 7.
+        assert!(overlaps.contains(&Overlap {
+            first: 6,
+            second: 7,
+            contains: false
+        }));
+    }
+
+    #[test]
+    fn crowded() {
+        let pairs = ["1-3,2-4", "3-5,9-9"].map(|s| s.parse::<Pair>().unwrap());
+        let assignments = assignments(&pairs);
+        assert_eq!(
+            covered_by_more_than(&assignments, 1),
+            vec![(2..3, 2), (3..4, 3), (4..5, 2)]
+        );
+        assert_eq!(
+            covered_by_more_than(&assignments, 0),
+            vec![
+                (1..2, 1),
+                (2..3, 2),
+                (3..4, 3),
+                (4..5, 2),
+                (5..6, 1),
+                (9..10, 1)
+            ]
+        );
+        assert!(covered_by_more_than(&assignments, 3).is_empty());
+    }
+
+    #[test]
+    fn cover() {
+        assert_eq!(minimum_cover(&assignments(&example())), vec![6, 7]);
+
+        let pairs = ["1-3,2-4", "3-5,9-9"].map(|s| s.parse::<Pair>().unwrap());
+        assert_eq!(minimum_cover(&assignments(&pairs)), vec![1, 3, 4]);
+    }
+}
@@ -0,0 +1,391 @@
+use std::fmt::Display;
+use std::io::Write;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use itertools::Itertools;
+
+pub mod drawing;
+pub mod solve;
+
+/// Stacks of crates, bottom crate first, numbered from 1 in the puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Stacks(pub Vec<Vec<String>>);
+
+impl Stacks {
+    /// The labels of the crates on top of each stack, skipping empty stacks.
+    pub fn tops(&self) -> String {
+        self.0.iter().filter_map(|stack| stack.last()).join("")
+    }
+
+    /// Moves the top `count` crates of stack `from` onto stack `to` in one
+    /// lift, keeping their order.
+    fn lift(&mut self, count: usize, from: usize, to: usize) -> Result<()> {
+        let len = self.stack(from)?.len();
+        self.stack(to)?;
+        if count > len {
+            Err(anyhow!("stack {} only has {} crates", from, len))?;
+        }
+
+        let crates = self.0[from - 1].split_off(len - count);
+        self.0[to - 1].extend(crates);
+        Ok(())
+    }
+
+    fn stack(&self, num: usize) -> Result<&Vec<String>> {
+        num.checked_sub(1)
+            .and_then(|idx| self.0.get(idx))
+            .ok_or(anyhow!("there is no stack {}", num))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl FromStr for Move {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // example: move 1 from 2 to 1
+        if let Some((count, from, to)) = s
+            .split_whitespace()
+            .filter_map(|digit| digit.parse::<usize>().ok())
+            .collect_tuple()
+        {
+            Ok(Move { count, from, to })
+        } else {
+            Err(anyhow!("Failed to parse move string: {}", s))
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+/// How a crane carries out a move.
+pub trait Crane {
+    /// The most crates lifted at once, keeping their order.
+    fn capacity(&self) -> usize;
+
+    fn apply(&self, stacks: &mut Stacks, mv: &Move) -> Result<()> {
+        if self.capacity() == 0 {
+            Err(anyhow!("the crane can't lift any crates"))?;
+        }
+
+        // Checks the whole move up front so a failed move leaves no trace.
+        stacks.stack(mv.to)?;
+        let len = stacks.stack(mv.from)?.len();
+        if mv.count > len {
+            Err(anyhow!("stack {} only has {} crates", mv.from, len))?;
+        }
+
+        let mut left = mv.count;
+        while left > 0 {
+            let count = left.min(self.capacity());
+            stacks.lift(count, mv.from, mv.to)?;
+            left -= count;
+        }
+        Ok(())
+    }
+}
+
+/// Moves crates one at a time (part 1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9000;
+
+/// Moves any number of crates at once (part 2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrateMover9001;
+
+/// Moves up to `capacity` crates at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LimitedCrane {
+    pub capacity: usize,
+}
+
+impl Crane for CrateMover9000 {
+    fn capacity(&self) -> usize {
+        1
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// The puzzle input: a drawing of the stacks, a blank line, then the moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Procedure {
+    pub stacks: Stacks,
+    pub moves: Vec<Move>,
+    /// The line of the input holding the first move.
+    pub first_move_line: usize,
+}
+
+impl Procedure {
+    /// The stacks once every move is done.
+    pub fn run(&self, crane: &impl Crane) -> Result<Stacks> {
+        let mut stacks = self.stacks.clone();
+        for idx in 0..self.moves.len() {
+            self.apply(crane, &mut stacks, idx)?;
+        }
+        Ok(stacks)
+    }
+
+    /// Checks every move can be done, reporting the first that can't.
+    pub fn validate(&self, crane: &impl Crane) -> Result<()> {
+        self.run(crane).map(|_| ())
+    }
+
+    /// The stacks after each move in turn.
+    pub fn replay<'a, C: Crane>(&'a self, crane: &'a C) -> Replay<'a, C> {
+        Replay {
+            crane,
+            procedure: self,
+            stacks: self.stacks.clone(),
+            next: 0,
+        }
+    }
+
+    /// Writes the starting stacks, then every move followed by the stacks
+    /// after it, stopping at the first move that fails.
+    pub fn write_replay(&self, crane: &impl Crane, mut out: impl Write) -> Result<()> {
+        writeln!(out, "{}", self.stacks)?;
+        for (mv, stacks) in self.moves.iter().zip(self.replay(crane)) {
+            writeln!(out, "\n{}\n{}", mv, stacks?)?;
+        }
+        Ok(())
+    }
+
+    fn apply(&self, crane: &impl Crane, stacks: &mut Stacks, idx: usize) -> Result<()> {
+        crane.apply(stacks, &self.moves[idx]).map_err(|e| {
+            anyhow!(
+                "Line {}: can't {}, {}",
+                self.first_move_line + idx,
+                self.moves[idx],
+                e
+            )
+        })
+    }
+}
+
+impl FromStr for Procedure {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (stacks, moves) = s
+            .split_once("\n\n")
+            .ok_or(anyhow!("Missing the blank line before the moves!"))?;
+        let first_move_line = stacks.lines().count() + 2;
+
+        Ok(Procedure {
+            stacks: stacks.parse()?,
+            moves: moves
+                .lines()
+                .enumerate()
+                .map(|(idx, mv)| {
+                    mv.parse()
+                        .map_err(|e| anyhow!("Line {}: {}", first_move_line + idx, e))
+                })
+                .collect::<Result<_>>()?,
+            first_move_line,
+        })
+    }
+}
+
+impl Display for Procedure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}\n", self.stacks)?;
+        for mv in &self.moves {
+            writeln!(f, "{}", mv)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the stacks after each move, see `Procedure::replay`. Stops
+/// after the first move that fails.
+pub struct Replay<'a, C> {
+    crane: &'a C,
+    procedure: &'a Procedure,
+    stacks: Stacks,
+    next: usize,
+}
+
+impl<C: Crane> Iterator for Replay<'_, C> {
+    type Item = Result<Stacks>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next >= self.procedure.moves.len() {
+            return None;
+        }
+        match self
+            .procedure
+            .apply(self.crane, &mut self.stacks, self.next)
+        {
+            Ok(()) => {
+                self.next += 1;
+                Some(Ok(self.stacks.clone()))
+            }
+            Err(e) => {
+                self.next = self.procedure.moves.len();
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stacks of single letter crates, bottom first.
+    fn stacks(stacks: &[&str]) -> Stacks {
+        Stacks(
+            stacks
+                .iter()
+                .map(|stack| stack.chars().map(String::from).collect())
+                .collect(),
+        )
+    }
+
+    const EXAMPLE: &str = concat!(
+        "    [D]    \n",
+        "[N] [C]    \n",
+        "[Z] [M] [P]\n",
+        " 1   2   3 \n",
+        "\n",
+        "move 1 from 2 to 1\n",
+        "move 3 from 1 to 3\n",
+        "move 2 from 2 to 1\n",
+        "move 1 from 1 to 2\n",
+    );
+
+    #[test]
+    fn round_trip() {
+        let procedure = EXAMPLE.parse::<Procedure>().unwrap();
+        assert_eq!(procedure.stacks, stacks(&["ZN", "MCD", "P"]));
+        assert_eq!(procedure.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn cranes() {
+        let procedure = EXAMPLE.parse::<Procedure>().unwrap();
+        assert_eq!(procedure.run(&CrateMover9000).unwrap().tops(), "CMZ");
+        assert_eq!(procedure.run(&CrateMover9001).unwrap().tops(), "MCD");
+        assert_eq!(
+            procedure.run(&LimitedCrane { capacity: 1 }).unwrap(),
+            procedure.run(&CrateMover9000).unwrap()
+        );
+
+        let mut stacks = stacks(&["ABCDE", ""]);
+        let mv = Move {
+            count: 5,
+            from: 1,
+            to: 2,
+        };
+        LimitedCrane { capacity: 2 }
+            .apply(&mut stacks, &mv)
+            .unwrap();
+        assert_eq!(stacks.0[1], vec!["D", "E", "B", "C", "A"]);
+        assert!(LimitedCrane { capacity: 2 }
+            .apply(&mut stacks, &mv)
+            .is_err());
+        assert!(CrateMover9001
+            .apply(
+                &mut stacks,
+                &Move {
+                    count: 0,
+                    from: 1,
+                    to: 3
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn replay() {
+        let procedure = EXAMPLE.parse::<Procedure>().unwrap();
+        let states = procedure
+            .replay(&CrateMover9000)
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(states.len(), 4);
+        assert_eq!(
+            states[0].to_string(),
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 "
+        );
+        assert_eq!(states[3], procedure.run(&CrateMover9000).unwrap());
+
+        let procedure = Procedure {
+            moves: vec![
+                Move {
+                    count: 5,
+                    from: 1,
+                    to: 2
+                };
+                3
+            ],
+            ..procedure
+        };
+        let replay = procedure.replay(&CrateMover9000).collect_vec();
+        assert_eq!(replay.len(), 1);
+        assert!(replay[0].is_err());
+    }
+
+    #[test]
+    fn write_replay() {
+        let procedure = EXAMPLE.parse::<Procedure>().unwrap();
+        let mut out = Vec::new();
+        procedure.write_replay(&CrateMover9001, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with(&format!("{}\n\nmove 1 from 2 to 1\n", procedure.stacks)));
+        assert!(out.ends_with(&format!(
+            "move 1 from 1 to 2\n{}\n",
+            procedure.run(&CrateMover9001).unwrap()
+        )));
+    }
+
+    #[test]
+    fn validate() {
+        let illegal = EXAMPLE.replace("move 2 from 2 to 1", "move 3 from 2 to 1");
+        let procedure = illegal.parse::<Procedure>().unwrap();
+        assert_eq!(
+            procedure.validate(&CrateMover9001).unwrap_err().to_string(),
+            "Line 8: can't move 3 from 2 to 1, stack 2 only has 2 crates"
+        );
+        assert!(EXAMPLE
+            .parse::<Procedure>()
+            .unwrap()
+            .validate(&CrateMover9000)
+            .is_ok());
+
+        let unknown = EXAMPLE.replace("move 1 from 1 to 2", "move 1 from 1 to 4");
+        let procedure = unknown.parse::<Procedure>().unwrap();
+        assert_eq!(
+            procedure.validate(&CrateMover9000).unwrap_err().to_string(),
+            "Line 9: can't move 1 from 1 to 4, there is no stack 4"
+        );
+
+        let garbled = EXAMPLE.replace("move 1 from 2 to 1", "move one from 2 to 1");
+        assert_eq!(
+            garbled.parse::<Procedure>().unwrap_err().to_string(),
+            "Line 6: Failed to parse move string: move one from 2 to 1"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+use itertools::Itertools;
+
+use super::Stacks;
+
+/// A crate in the drawing, `[label]`, with the columns it spans.
+struct DrawnCrate {
+    label: String,
+    columns: RangeInclusive<usize>,
+}
+
+/// The whitespace separated words of `line` with the columns they span.
+fn words(line: &str) -> Vec<(String, RangeInclusive<usize>)> {
+    line.chars()
+        .enumerate()
+        .group_by(|(_, c)| c.is_whitespace())
+        .into_iter()
+        .filter(|(blank, _)| !blank)
+        .map(|(_, word)| {
+            let word = word.collect_vec();
+            let columns = word[0].0..=word[word.len() - 1].0;
+            (word.into_iter().map(|(_, c)| c).collect(), columns)
+        })
+        .collect()
+}
+
+fn crates(line: &str, line_num: usize) -> Result<Vec<DrawnCrate>> {
+    let chars = line.chars().collect_vec();
+    let mut crates = Vec::new();
+    let mut col = 0;
+    while col < chars.len() {
+        match chars[col] {
+            c if c.is_whitespace() => col += 1,
+            '[' => {
+                let close = (col + 1..chars.len())
+                    .find(|&idx| chars[idx] == ']')
+                    .ok_or(anyhow!(
+                        "Line {}, column {}: crate is missing its closing ']'",
+                        line_num,
+                        col + 1
+                    ))?;
+                let label = chars[col + 1..close].iter().collect::<String>();
+                if label.is_empty() || label.contains(char::is_whitespace) {
+                    Err(anyhow!(
+                        "Line {}, column {}: crate label {:?} is empty or has spaces",
+                        line_num,
+                        col + 1,
+                        label
+                    ))?;
+                }
+                crates.push(DrawnCrate {
+                    label,
+                    columns: col..=close,
+                });
+                col = close + 1;
+            }
+            c => Err(anyhow!(
+                "Line {}, column {}: expected a crate, found {:?}",
+                line_num,
+                col + 1,
+                c
+            ))?,
+        }
+    }
+    Ok(crates)
+}
+
+fn overlap(a: &RangeInclusive<usize>, b: &RangeInclusive<usize>) -> bool {
+    a.start() <= b.end() && b.start() <= a.end()
+}
+
+impl FromStr for Stacks {
+    type Err = Error;
+
+    /// Parses a drawing of the stacks, the last line numbering them. Each
+    /// crate belongs to the stack whose number it sits above, so stacks may be
+    /// numbered past 9 and labels may be any width.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = s.lines().collect_vec();
+        let (numbers, drawing) = lines
+            .split_last()
+            .ok_or(anyhow!("Missing the line numbering the stacks!"))?;
+        let number_line = lines.len();
+
+        let numbers = words(numbers);
+        for (idx, (number, columns)) in numbers.iter().enumerate() {
+            if number.parse::<usize>().ok() != Some(idx + 1) {
+                Err(anyhow!(
+                    "Line {}, column {}: expected stack number {}, found {:?}",
+                    number_line,
+                    columns.start() + 1,
+                    idx + 1,
+                    number
+                ))?;
+            }
+        }
+        if numbers.is_empty() {
+            Err(anyhow!("Line {}: no stacks are numbered", number_line))?;
+        }
+
+        // Bottom line first, remembering where the first gap in each stack is.
+        let mut stacks = vec![Vec::new(); numbers.len()];
+        for (line_idx, line) in drawing.iter().enumerate().rev() {
+            let line_num = line_idx + 1;
+            let mut filled = vec![false; numbers.len()];
+            for drawn in crates(line, line_num)? {
+                let under = (0..numbers.len())
+                    .filter(|&stack| overlap(&numbers[stack].1, &drawn.columns))
+                    .collect_vec();
+                let stack = match under[..] {
+                    [stack] => stack,
+                    [] => Err(anyhow!(
+                        "Line {}, column {}: crate [{}] is not above any stack number",
+                        line_num,
+                        drawn.columns.start() + 1,
+                        drawn.label
+                    ))?,
+                    _ => Err(anyhow!(
+                        "Line {}, column {}: crate [{}] is above stacks {}",
+                        line_num,
+                        drawn.columns.start() + 1,
+                        drawn.label,
+                        under.iter().map(|stack| stack + 1).join(" and ")
+                    ))?,
+                };
+
+                if filled[stack] {
+                    Err(anyhow!(
+                        "Line {}, column {}: a second crate above stack {}",
+                        line_num,
+                        drawn.columns.start() + 1,
+                        stack + 1
+                    ))?;
+                }
+                if stacks[stack].len() != drawing.len() - 1 - line_idx {
+                    Err(anyhow!(
+                        "Line {}, column {}: crate [{}] floats over a gap in stack {}",
+                        line_num,
+                        drawn.columns.start() + 1,
+                        drawn.label,
+                        stack + 1
+                    ))?;
+                }
+                filled[stack] = true;
+                stacks[stack].push(drawn.label);
+            }
+        }
+
+        Ok(Stacks(stacks))
+    }
+}
+
+impl Display for Stacks {
+    /// Draws the stacks as in the puzzle input, every column as wide as its
+    /// widest crate or its number, whichever is wider.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let widths = self
+            .0
+            .iter()
+            .enumerate()
+            .map(|(idx, stack)| {
+                let widest = stack.iter().map(|label| label.chars().count()).max();
+                (widest.unwrap_or(1) + 2).max((idx + 1).to_string().len() + 2)
+            })
+            .collect_vec();
+
+        let height = self.0.iter().map(|stack| stack.len()).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row = self
+                .0
+                .iter()
+                .zip(&widths)
+                .map(|(stack, &width)| match stack.get(level) {
+                    Some(label) => format!("{:<width$}", format!("[{}]", label)),
+                    None => " ".repeat(width),
+                })
+                .join(" ");
+            writeln!(f, "{}", row)?;
+        }
+
+        let numbers = widths
+            .iter()
+            .enumerate()
+            .map(|(idx, &width)| format!("{:^width$}", idx + 1))
+            .join(" ");
+        write!(f, "{}", numbers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(drawing: &str) -> String {
+        drawing.parse::<Stacks>().unwrap_err().to_string()
+    }
+
+    #[test]
+    fn ragged_lines() {
+        let stacks = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3"
+            .parse::<Stacks>()
+            .unwrap();
+        assert_eq!(stacks.tops(), "NDP");
+        assert_eq!(stacks.0[1], vec!["M", "C", "D"]);
+    }
+
+    #[test]
+    fn wide_drawing() {
+        let drawing = [
+            "[AB]                                           [K] ",
+            "[CD] [E] [F] [G] [H] [I] [J] [K] [L] [M]  [N]  [XYZ]",
+            " 1    2   3   4   5   6   7   8   9  10   11    12  ",
+        ]
+        .join("\n");
+        let stacks = drawing.parse::<Stacks>().unwrap();
+        assert_eq!(stacks.0.len(), 12);
+        assert_eq!(stacks.0[0], vec!["CD", "AB"]);
+        assert_eq!(stacks.0[11], vec!["XYZ", "K"]);
+
+        // Rendering evens out the spacing, but parses back the same.
+        assert_eq!(stacks.to_string().parse::<Stacks>().unwrap(), stacks);
+        assert!(stacks.to_string().ends_with(" 10   11   12  "));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            error("[A] [B]\n 1   3"),
+            "Line 2, column 6: expected stack number 2, found \"3\""
+        );
+        assert_eq!(
+            error("[A]  [B\n 1   2"),
+            "Line 1, column 6: crate is missing its closing ']'"
+        );
+        assert_eq!(
+            error("[A] x\n 1   2"),
+            "Line 1, column 5: expected a crate, found 'x'"
+        );
+        assert_eq!(
+            error("[A]     [B]\n 1   2"),
+            "Line 1, column 9: crate [B] is not above any stack number"
+        );
+        assert_eq!(
+            error("[AB]\n 1 2"),
+            "Line 1, column 1: crate [AB] is above stacks 1 and 2"
+        );
+        assert_eq!(
+            error("    [A]\n\n 1   2"),
+            "Line 1, column 5: crate [A] floats over a gap in stack 2"
+        );
+        assert_eq!(error(""), "Missing the line numbering the stacks!");
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::{HashMap, VecDeque};
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+use super::{Crane, Move, Stacks};
+
+/// Every move that can be made from `stacks`.
+fn moves(stacks: &Stacks) -> impl Iterator<Item = Move> + '_ {
+    (1..=stacks.0.len())
+        .cartesian_product(1..=stacks.0.len())
+        .filter(|(from, to)| from != to)
+        .flat_map(move |(from, to)| {
+            (1..=stacks.0[from - 1].len()).map(move |count| Move { count, from, to })
+        })
+}
+
+/// The fewest moves taking `start` to `target` with `crane`.
+///
+/// This is a breadth first search over the arrangements of the crates, so it
+/// is only practical for a handful of crates.
+pub fn solve(start: &Stacks, target: &Stacks, crane: &impl Crane) -> Result<Vec<Move>> {
+    let crates = |stacks: &Stacks| stacks.0.iter().flatten().sorted().cloned().collect_vec();
+    if start.0.len() != target.0.len() || crates(start) != crates(target) {
+        Err(anyhow!(
+            "The target doesn't have the same stacks and crates as the start"
+        ))?;
+    }
+
+    // How each arrangement was first reached.
+    let mut previous: HashMap<Stacks, Option<(Stacks, Move)>> = HashMap::new();
+    previous.insert(start.clone(), None);
+    let mut queue = VecDeque::from([start.clone()]);
+
+    while let Some(stacks) = queue.pop_front() {
+        if &stacks == target {
+            let mut path = Vec::new();
+            let mut current = &stacks;
+            while let Some((before, mv)) = &previous[current] {
+                path.push(*mv);
+                current = before;
+            }
+            path.reverse();
+            return Ok(path);
+        }
+
+        for mv in moves(&stacks) {
+            let mut next = stacks.clone();
+            crane.apply(&mut next, &mv)?;
+            if !previous.contains_key(&next) {
+                previous.insert(next.clone(), Some((stacks.clone(), mv)));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    Err(anyhow!("The target can't be reached with this crane"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{CrateMover9000, CrateMover9001, LimitedCrane, Procedure};
+    use super::*;
+
+    fn check(start: &Stacks, target: &Stacks, crane: &impl Crane, expected: usize) {
+        let moves = solve(start, target, crane).unwrap();
+        assert_eq!(moves.len(), expected, "{:?}", moves);
+
+        let mut stacks = start.clone();
+        for mv in &moves {
+            crane.apply(&mut stacks, mv).unwrap();
+        }
+        assert_eq!(&stacks, target);
+    }
+
+    #[test]
+    fn example() {
+        let procedure = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n\
+                         move 1 from 2 to 1\nmove 3 from 1 to 3\n\
+                         move 2 from 2 to 1\nmove 1 from 1 to 2"
+            .parse::<Procedure>()
+            .unwrap();
+
+        let target = procedure.run(&CrateMover9000).unwrap();
+        check(&procedure.stacks, &target, &CrateMover9000, 3);
+        let target = procedure.run(&CrateMover9001).unwrap();
+        check(&procedure.stacks, &target, &CrateMover9001, 4);
+    }
+
+    #[test]
+    fn order_matters() {
+        let start = "[B]    \n[A]    \n 1   2 ".parse::<Stacks>().unwrap();
+        let target = "    [B]\n    [A]\n 1   2 ".parse::<Stacks>().unwrap();
+        check(&start, &target, &CrateMover9001, 1);
+        check(&start, &target, &LimitedCrane { capacity: 2 }, 1);
+
+        // One at a time, the crates only ever swap over between two stacks.
+        assert!(solve(&start, &target, &CrateMover9000).is_err());
+
+        let start = "[B]        \n[A]        \n 1   2   3 "
+            .parse::<Stacks>()
+            .unwrap();
+        let target = "    [B]    \n    [A]    \n 1   2   3 "
+            .parse::<Stacks>()
+            .unwrap();
+        check(&start, &target, &CrateMover9000, 2);
+    }
+
+    #[test]
+    fn mismatched_target() {
+        let start = "[A] [B]\n 1   2 ".parse::<Stacks>().unwrap();
+        let target = "[A] [C]\n 1   2 ".parse::<Stacks>().unwrap();
+        assert!(solve(&start, &target, &CrateMover9001).is_err());
+    }
+}
@@ -1,9 +1,12 @@
-use std::{collections::HashSet, fs};
+use std::fs::File;
 
 use anyhow::Result;
 
+use aoc::day06::first_marker;
+use aoc::input_file;
 use aoc::time_it;
-use itertools::Itertools;
+
+const WINDOW_SIZE: usize = 4;
 
 fn main() -> Result<()> {
     time_it(|| solution())?;
@@ -11,26 +14,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let input = fs::read_to_string("input/day06.txt")?;
-    Ok(find_marker(&input))
-}
-
-type Window<'a> = HashSet<&'a char>;
-const WINDOW_SIZE: usize = 4;
-
-fn find_marker(s: &str) -> usize {
-    s.chars()
-        .collect_vec()
-        .windows(WINDOW_SIZE)
-        .enumerate()
-        .find_map(|(idx, chars)| {
-            if Window::from_iter(chars.iter()).len() == WINDOW_SIZE {
-                Some(idx + WINDOW_SIZE)
-            } else {
-                None
-            }
-        })
-        .unwrap()
+    first_marker(File::open(input_file(6)?)?, WINDOW_SIZE)
 }
 
 #[cfg(test)]
@@ -46,7 +30,10 @@ mod tests {
     #[case("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10)]
     #[case("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11)]
     fn find_marker_tests(#[case] input: &str, #[case] expected: usize) {
-        assert_eq!(expected, find_marker(input))
+        assert_eq!(
+            expected,
+            first_marker(input.as_bytes(), WINDOW_SIZE).unwrap()
+        )
     }
 
     #[test]
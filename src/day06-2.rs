@@ -1,36 +1,39 @@
-use std::{collections::HashSet, fs};
+use std::fs::File;
 
 use anyhow::Result;
 
+use aoc::day06::first_marker;
+use aoc::day06::longest_distinct_run;
+use aoc::day06::Markers;
+use aoc::input_file;
 use aoc::time_it;
-use itertools::Itertools;
 
+const WINDOW_SIZE: usize = 14;
+
+/// Pass `--all` to list every marker and the longest run of distinct characters.
 fn main() -> Result<()> {
-    time_it(|| solution())?;
+    match std::env::args().nth(1).as_deref() {
+        Some("--all") => all()?,
+        _ => time_it(|| solution())?,
+    }
     Ok(())
 }
 
 fn solution() -> Result<usize> {
-    let input = fs::read_to_string("input/day06.txt")?;
-    Ok(find_marker(&input))
+    first_marker(File::open(input_file(6)?)?, WINDOW_SIZE)
 }
 
-type Window<'a> = HashSet<&'a char>;
-const WINDOW_SIZE: usize = 14;
-
-fn find_marker(s: &str) -> usize {
-    s.chars()
-        .collect_vec()
-        .windows(WINDOW_SIZE)
-        .enumerate()
-        .find_map(|(idx, chars)| {
-            if Window::from_iter(chars.iter()).len() == WINDOW_SIZE {
-                Some(idx + WINDOW_SIZE)
-            } else {
-                None
-            }
-        })
-        .unwrap()
+fn all() -> Result<()> {
+    for marker in Markers::new(File::open(input_file(6)?)?, WINDOW_SIZE)? {
+        println!("{}", marker?);
+    }
+    let run = longest_distinct_run(File::open(input_file(6)?)?)?;
+    println!(
+        "longest run: {} characters from {}",
+        run.len(),
+        run.start + 1
+    );
+    Ok(())
 }
 
 #[cfg(test)]
@@ -46,7 +49,10 @@ mod tests {
     #[case("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 29)]
     #[case("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 26)]
     fn find_marker_tests(#[case] input: &str, #[case] expected: usize) {
-        assert_eq!(expected, find_marker(input))
+        assert_eq!(
+            expected,
+            first_marker(input.as_bytes(), WINDOW_SIZE).unwrap()
+        )
     }
 
     #[test]
@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+use std::io::{BufReader, Bytes, Read};
+use std::ops::Range;
+
+use anyhow::{anyhow, Result};
+
+/// The last `size` characters of a stream along with how often each byte
+/// appears among them.
+struct Window {
+    size: usize,
+    recent: VecDeque<u8>,
+    counts: [usize; 256],
+    distinct: usize,
+}
+
+impl Window {
+    fn push(&mut self, byte: u8) {
+        self.counts[byte as usize] += 1;
+        if self.counts[byte as usize] == 1 {
+            self.distinct += 1;
+        }
+
+        self.recent.push_back(byte);
+        if self.recent.len() > self.size {
+            let old = self.recent.pop_front().unwrap();
+            self.counts[old as usize] -= 1;
+            if self.counts[old as usize] == 0 {
+                self.distinct -= 1;
+            }
+        }
+    }
+}
+
+/// The bytes of a stream but for the line ending at its end. Line ending bytes
+/// are held back until something other than a line ending follows them.
+struct TrimEnd<R> {
+    bytes: Bytes<BufReader<R>>,
+    held: VecDeque<u8>,
+    ready: VecDeque<u8>,
+}
+
+impl<R: Read> TrimEnd<R> {
+    fn new(reader: R) -> Self {
+        TrimEnd {
+            bytes: BufReader::new(reader).bytes(),
+            held: VecDeque::new(),
+            ready: VecDeque::new(),
+        }
+    }
+}
+
+impl<R: Read> Iterator for TrimEnd<R> {
+    type Item = Result<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(byte) = self.ready.pop_front() {
+            return Some(Ok(byte));
+        }
+        loop {
+            match self.bytes.next()? {
+                Ok(byte @ (b'\n' | b'\r')) => self.held.push_back(byte),
+                Ok(byte) => {
+                    self.ready.append(&mut self.held);
+                    self.ready.push_back(byte);
+                    return self.ready.pop_front().map(Ok);
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+/// Iterator over the positions of every marker in a stream, a marker being
+/// `window` characters in a row that are all different. Positions count the
+/// bytes read up to and including the end of the marker, as in the puzzle,
+/// so are offsets into the stream. Only a line ending at the very end of the
+/// stream is left out.
+///
+/// Only the last `window` characters are kept, so every character is handled
+/// in constant time and memory.
+pub struct Markers<R> {
+    bytes: TrimEnd<R>,
+    window: Window,
+    position: usize,
+}
+
+impl<R: Read> Markers<R> {
+    pub fn new(reader: R, window: usize) -> Result<Self> {
+        if window == 0 {
+            Err(anyhow!("A marker needs a window of at least one character"))?;
+        }
+
+        Ok(Markers {
+            bytes: TrimEnd::new(reader),
+            window: Window {
+                size: window,
+                recent: VecDeque::new(),
+                counts: [0; 256],
+                distinct: 0,
+            },
+            position: 0,
+        })
+    }
+}
+
+impl<R: Read> Iterator for Markers<R> {
+    type Item = Result<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for byte in self.bytes.by_ref() {
+            match byte {
+                Ok(byte) => self.window.push(byte),
+                Err(e) => return Some(Err(e)),
+            }
+            self.position += 1;
+            if self.window.distinct == self.window.size {
+                return Some(Ok(self.position));
+            }
+        }
+        None
+    }
+}
+
+/// The position of the first marker of `window` characters.
+pub fn first_marker(reader: impl Read, window: usize) -> Result<usize> {
+    Markers::new(reader, window)?
+        .next()
+        .unwrap_or(Err(anyhow!("No marker of {} characters", window)))
+}
+
+/// The longest run of characters that are all different, as the range of
+/// positions covered, starting from 0 and leaving out a line ending at the
+/// end of the stream. The first is picked when several are as long.
+pub fn longest_distinct_run(reader: impl Read) -> Result<Range<usize>> {
+    let mut last_seen = [None; 256];
+    let mut start = 0;
+    let mut longest = 0..0;
+
+    let mut position = 0;
+    for byte in TrimEnd::new(reader) {
+        let byte = byte?;
+        if let Some(seen) = last_seen[byte as usize] {
+            start = start.max(seen + 1);
+        }
+        last_seen[byte as usize] = Some(position);
+        position += 1;
+
+        if position - start > longest.len() {
+            longest = start..position;
+        }
+    }
+
+    Ok(longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+    #[test]
+    fn every_marker() {
+        let markers = Markers::new("abcabd\n".as_bytes(), 3)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(markers, vec![3, 4, 5, 6]);
+
+        let markers = Markers::new("aabbab".as_bytes(), 2)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(markers, vec![3, 5, 6]);
+
+        let markers = Markers::new("ab c\nd".as_bytes(), 4).unwrap();
+        assert_eq!(markers.collect::<Result<Vec<_>>>().unwrap(), vec![4, 5, 6]);
+        let markers = Markers::new("ab\r\n".as_bytes(), 2).unwrap();
+        assert_eq!(markers.collect::<Result<Vec<_>>>().unwrap(), vec![2]);
+        let mut markers = Markers::new(EXAMPLE.as_bytes(), usize::MAX).unwrap();
+        assert!(markers.next().is_none());
+    }
+
+    #[test]
+    fn first() {
+        assert_eq!(first_marker(EXAMPLE.as_bytes(), 4).unwrap(), 7);
+        assert_eq!(first_marker(EXAMPLE.as_bytes(), 14).unwrap(), 19);
+        assert_eq!(first_marker(EXAMPLE.as_bytes(), 1).unwrap(), 1);
+        assert!(first_marker(EXAMPLE.as_bytes(), 30).is_err());
+        assert!(first_marker(EXAMPLE.as_bytes(), 0).is_err());
+    }
+
+    #[test]
+    fn longest_run() {
+        assert_eq!(longest_distinct_run(EXAMPLE.as_bytes()).unwrap(), 12..30);
+        assert_eq!(longest_distinct_run("aaaa\n".as_bytes()).unwrap(), 0..1);
+        assert_eq!(longest_distinct_run("abcabcd".as_bytes()).unwrap(), 3..7);
+        assert_eq!(longest_distinct_run("".as_bytes()).unwrap(), 0..0);
+        assert_eq!(longest_distinct_run("ab\nab c\n".as_bytes()).unwrap(), 2..7);
+    }
+}
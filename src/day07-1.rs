@@ -1,118 +1,89 @@
-use std::collections::HashMap;
-use std::ops::Add;
-use std::str::FromStr;
-
 use anyhow::anyhow;
-use anyhow::Error;
 use anyhow::Result;
 
 use aoc::collect_lines;
+use aoc::day07::diff::diff;
+use aoc::day07::disk;
+use aoc::day07::interpret::Interpreter;
+use aoc::day07::tree::Tree;
+use aoc::day07::TerminalLine;
+use aoc::input_file;
 use aoc::time_it;
 
+const SMALL_DIR_SIZE: usize = 100000;
+
+/// Pass `--tree` to print the directory tree, `--du` for every directory by
+/// size, `--find PATTERN` for the paths matching a glob, or `--check` for
+/// anything odd in the transcript.
+///
+/// `--export DIR` writes the tree out to `DIR` with sparse files, while
+/// `--transcript DIR` prints a transcript of a real directory and
+/// `--du-check DIR` compares that transcript with `du`.
+///
+/// `--diff BEFORE AFTER` lists what differs between two transcript files.
 fn main() -> Result<()> {
-    time_it(|| solution())?;
-    Ok(())
-}
-
-fn solution() -> Result<usize> {
-    let lines = collect_lines::<TerminalLine>("input/day07.txt")?;
-
-    let mut total_sizes: HashMap<String, usize> = HashMap::new();
-    let mut curdir = SimplePath::new();
-
-    for line in lines {
-        update_size(line, &mut curdir, &mut total_sizes)?;
-    }
-    while curdir.dirs.len() != 1 {
-        update_size(
-            TerminalLine::CD("..".to_string()),
-            &mut curdir,
-            &mut total_sizes,
-        )?;
-    }
-
-    println!("{total_sizes:?}");
-
-    Ok(total_sizes
-        .into_iter()
-        .filter_map(|(_, size)| (size < 100000).then_some(size))
-        .sum::<usize>())
-}
-
-fn update_size(
-    line: TerminalLine,
-    curdir: &mut SimplePath,
-    total_sizes: &mut HashMap<String, usize>,
-) -> Result<()> {
-    match line {
-        TerminalLine::CD(dir) => {
-            if dir == ".." {
-                let child_size = total_sizes.get(&curdir.to_string()).unwrap().clone();
-                curdir.push(&dir)?;
-                let total_size = total_sizes.get_mut(&curdir.to_string()).unwrap();
-                *total_size += child_size;
-            } else {
-                curdir.push(&dir)?;
-                total_sizes.insert(curdir.to_string(), 0);
+    let args = std::env::args().collect::<Vec<_>>();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("--tree") => print!("{}", tree()?),
+        Some("--du") => {
+            for usage in tree()?.du() {
+                println!("{}", usage);
             }
         }
-        TerminalLine::File(size, _) => {
-            let total_size = total_sizes.get_mut(&curdir.to_string()).unwrap();
-            *total_size += size;
+        Some("--find") => {
+            let tree = tree()?;
+            for id in tree.glob(args.get(2).map_or("*", |pattern| pattern.as_str())) {
+                println!("{}", tree.path(id));
+            }
         }
-        _ => {}
-    }
-    Ok(())
-}
-
-struct SimplePath {
-    dirs: Vec<String>,
-}
-
-impl SimplePath {
-    fn new() -> Self {
-        SimplePath { dirs: vec![] }
-    }
-
-    fn push(&mut self, s: &str) -> Result<()> {
-        match s {
-            ".." => {
-                let _ = self.dirs.pop().ok_or(anyhow!("Already at root!"))?;
+        Some("--check") => {
+            let lines = collect_lines::<TerminalLine>(input_file(7)?)?;
+            for warning in Interpreter::run(&lines)?.warnings {
+                println!("{}", warning);
             }
-            "/" => self.dirs.push("".to_string()),
-            s => self.dirs.push(s.to_string()),
         }
-
-        Ok(())
-    }
-}
-
-impl ToString for SimplePath {
-    fn to_string(&self) -> String {
-        self.dirs.join("/").add("/")
+        Some(flag @ ("--export" | "--transcript" | "--du-check")) => {
+            let dir = args.get(2).ok_or(anyhow!("{} needs a directory", flag))?;
+            match flag {
+                "--export" => disk::export(&tree()?, dir)?,
+                "--transcript" => {
+                    for line in disk::transcript(dir)? {
+                        println!("{}", line);
+                    }
+                }
+                _ => {
+                    for mismatch in disk::du_mismatches(dir)? {
+                        println!("{}", mismatch);
+                    }
+                }
+            }
+        }
+        Some("--diff") => {
+            let [before, after] = [2, 3].map(|idx| {
+                let fname = args
+                    .get(idx)
+                    .ok_or(anyhow!("--diff needs two transcripts"))?;
+                Tree::from_transcript(&collect_lines::<TerminalLine>(fname)?)
+            });
+            for difference in diff(&before?, &after?) {
+                println!("{}", difference);
+            }
+        }
+        _ => time_it(|| solution())?,
     }
+    Ok(())
 }
 
-#[derive(Debug, PartialEq)]
-enum TerminalLine {
-    CD(String),
-    LS,
-    Dir(String),
-    File(usize, String),
+fn tree() -> Result<Tree> {
+    Tree::from_transcript(&collect_lines::<TerminalLine>(input_file(7)?)?)
 }
 
-impl FromStr for TerminalLine {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.split_whitespace().collect::<Vec<_>>().as_slice() {
-            ["$", "cd", dest] => Ok(TerminalLine::CD(dest.to_string())),
-            ["$", "ls"] => Ok(TerminalLine::LS),
-            ["dir", name] => Ok(TerminalLine::Dir(name.to_string())),
-            [size, name] => Ok(TerminalLine::File(size.parse()?, name.to_string())),
-            _ => Err(anyhow!("Unexpected terminal line: {}", s)),
-        }
-    }
+fn solution() -> Result<usize> {
+    Ok(tree()?
+        .du()
+        .into_iter()
+        .filter_map(|usage| (usage.size <= SMALL_DIR_SIZE).then_some(usage.size))
+        .sum::<usize>())
 }
 
 #[cfg(test)]
@@ -134,4 +105,23 @@ mod tests {
     fn sln() {
         assert_eq!(solution().unwrap(), 1543140);
     }
+
+    #[test]
+    fn on_disk() {
+        let dir = disk::export_to_temp(&tree().unwrap()).unwrap();
+        let lines = disk::transcript(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let read_back = Tree::from_transcript(&lines.unwrap()).unwrap();
+        assert_eq!(read_back.to_string(), tree().unwrap().to_string());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn matches_du() {
+        let dir = disk::export_to_temp(&tree().unwrap()).unwrap();
+        let mismatches = disk::du_mismatches(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(mismatches.unwrap(), Vec::<String>::new());
+    }
 }
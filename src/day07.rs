@@ -0,0 +1,86 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+pub mod diff;
+pub mod disk;
+pub mod interpret;
+pub mod plan;
+pub mod tree;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TerminalLine {
+    CD(String),
+    LS,
+    Dir(String),
+    File(usize, String),
+}
+
+impl FromStr for TerminalLine {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_whitespace().collect::<Vec<_>>().as_slice() {
+            ["$", "cd", dest] => Ok(TerminalLine::CD(dest.to_string())),
+            ["$", "ls"] => Ok(TerminalLine::LS),
+            ["dir", name] => Ok(TerminalLine::Dir(name.to_string())),
+            [size, name] => Ok(TerminalLine::File(size.parse()?, name.to_string())),
+            _ => Err(anyhow!("Unexpected terminal line: {}", s)),
+        }
+    }
+}
+
+impl Display for TerminalLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TerminalLine::CD(dest) => write!(f, "$ cd {}", dest),
+            TerminalLine::LS => write!(f, "$ ls"),
+            TerminalLine::Dir(name) => write!(f, "dir {}", name),
+            TerminalLine::File(size, name) => write!(f, "{} {}", size, name),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) const EXAMPLE: &str = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+";
+
+#[cfg(test)]
+pub(crate) fn example() -> Vec<TerminalLine> {
+    EXAMPLE.lines().map(|line| line.parse().unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        for line in EXAMPLE.lines() {
+            assert_eq!(line.parse::<TerminalLine>().unwrap().to_string(), line);
+        }
+    }
+}
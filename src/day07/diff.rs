@@ -0,0 +1,174 @@
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+use super::tree::{NodeId, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Added,
+    Removed,
+    Resized,
+}
+
+/// A file or directory that differs between two trees. A directory's size is
+/// the total of the files below it, so every change to a file also shows up
+/// as a resize of the directories it is in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub path: String,
+    pub is_dir: bool,
+    pub change: Change,
+    pub before: usize,
+    pub after: usize,
+}
+
+impl Difference {
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+}
+
+/// How `after` differs from `before`, in path order with every directory
+/// before what is in it. A path that turned from a file into a directory, or
+/// back, is removed and added again.
+pub fn diff(before: &Tree, after: &Tree) -> Vec<Difference> {
+    let sizes = (before.sizes(), after.sizes());
+    let mut differences = Vec::new();
+    compare(
+        (before, after),
+        &sizes,
+        (Some(Tree::ROOT), Some(Tree::ROOT)),
+        &mut differences,
+    );
+    differences
+}
+
+fn compare(
+    trees: (&Tree, &Tree),
+    sizes: &(Vec<usize>, Vec<usize>),
+    nodes: (Option<NodeId>, Option<NodeId>),
+    differences: &mut Vec<Difference>,
+) {
+    let is_dir = (
+        nodes.0.is_some_and(|id| trees.0.node(id).is_dir()),
+        nodes.1.is_some_and(|id| trees.1.node(id).is_dir()),
+    );
+
+    match nodes {
+        (Some(before), Some(after)) if is_dir.0 == is_dir.1 => {
+            if sizes.0[before] != sizes.1[after] {
+                differences.push(Difference {
+                    path: trees.0.path(before),
+                    is_dir: is_dir.0,
+                    change: Change::Resized,
+                    before: sizes.0[before],
+                    after: sizes.1[after],
+                });
+            }
+        }
+        _ => {
+            if let Some(before) = nodes.0 {
+                differences.push(Difference {
+                    path: trees.0.path(before),
+                    is_dir: is_dir.0,
+                    change: Change::Removed,
+                    before: sizes.0[before],
+                    after: 0,
+                });
+            }
+            if let Some(after) = nodes.1 {
+                differences.push(Difference {
+                    path: trees.1.path(after),
+                    is_dir: is_dir.1,
+                    change: Change::Added,
+                    before: 0,
+                    after: sizes.1[after],
+                });
+            }
+        }
+    }
+
+    // Only the children of directories on both sides line up with each other.
+    let nodes = match (is_dir, nodes) {
+        ((true, true), nodes) => nodes,
+        ((true, false), (before, _)) => (before, None),
+        ((false, true), (_, after)) => (None, after),
+        _ => return,
+    };
+    let names = |tree: &Tree, id: Option<NodeId>| {
+        id.into_iter()
+            .flat_map(|id| tree.children(id))
+            .map(|child| tree.node(child).name.clone())
+            .collect::<Vec<_>>()
+    };
+    let names = names(trees.0, nodes.0)
+        .into_iter()
+        .chain(names(trees.1, nodes.1))
+        .collect::<BTreeSet<_>>();
+
+    for name in names {
+        let children = (
+            nodes.0.and_then(|id| trees.0.child(id, &name)),
+            nodes.1.and_then(|id| trees.1.child(id, &name)),
+        );
+        compare(trees, sizes, children, differences);
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = match self.change {
+            Change::Added => '+',
+            Change::Removed => '-',
+            Change::Resized => '~',
+        };
+        let kind = if self.is_dir { "dir" } else { "file" };
+        write!(
+            f,
+            "{} {:>4} {:>+10} {}",
+            sign,
+            kind,
+            self.delta(),
+            self.path
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example;
+    use super::*;
+    use itertools::Itertools;
+
+    fn tree(transcript: &str) -> Tree {
+        let lines = transcript
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect_vec();
+        Tree::from_transcript(&lines).unwrap()
+    }
+
+    #[test]
+    fn unchanged() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        assert!(diff(&tree, &tree).is_empty());
+    }
+
+    #[test]
+    fn changes() {
+        let before =
+            tree("$ cd /\n$ ls\ndir a\n10 b\n5 c\n$ cd a\n$ ls\ndir d\n3 e\n$ cd d\n$ ls\n1 f");
+        let after = tree("$ cd /\n$ ls\ndir a\n10 b\ndir c\n$ cd a\n$ ls\n7 e\n$ cd /c\n$ ls\n4 g");
+        assert_eq!(
+            diff(&before, &after).iter().join("\n"),
+            "~  dir         +2 /\n\
+             ~  dir         +3 /a\n\
+             -  dir         -1 /a/d\n\
+             - file         -1 /a/d/f\n\
+             ~ file         +4 /a/e\n\
+             - file         -5 /c\n\
+             +  dir         +4 /c\n\
+             + file         +4 /c/g"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+use super::tree::{Kind, Tree};
+use super::TerminalLine;
+
+/// Writes `tree` out below `dir`, every file a sparse file of its size so
+/// even huge trees take next to no disk space. Nothing that is already there
+/// is overwritten, and every path has to stay below `dir`.
+pub fn export(tree: &Tree, dir: impl AsRef<Path>) -> Result<()> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+
+    for id in tree.ids().skip(1) {
+        let segments = tree.segments(id);
+        let below = segments.iter().all(|segment| {
+            matches!(
+                Path::new(segment).components().collect_vec()[..],
+                [Component::Normal(_)]
+            )
+        });
+        if !below {
+            Err(anyhow!(
+                "{} would be written outside {}",
+                tree.path(id),
+                dir.display()
+            ))?;
+        }
+
+        let path = dir.join(segments.join("/"));
+        match tree.node(id).kind {
+            Kind::Dir(_) => fs::create_dir(&path)?,
+            Kind::File(size) => fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)?
+                .set_len(size as u64)?,
+        }
+    }
+    Ok(())
+}
+
+/// Exports `tree` to a new directory in the system's temporary directory,
+/// returning its path. Removing it is up to the caller.
+pub fn export_to_temp(tree: &Tree) -> Result<PathBuf> {
+    let dir = std::env::temp_dir().join(format!(
+        "aoc-day07-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_nanos()
+    ));
+    export(tree, &dir)?;
+    Ok(dir)
+}
+
+/// A terminal session that `cd`s into and lists every directory below `dir`,
+/// in the puzzle's format. Entries are in name order. Anything that isn't a
+/// directory, symlinks included, is listed as a file of its own size without
+/// following links, which is how `du --apparent-size` counts it.
+pub fn transcript(dir: impl AsRef<Path>) -> Result<Vec<TerminalLine>> {
+    let mut lines = vec![TerminalLine::CD("/".to_string())];
+    list(dir.as_ref(), &mut lines)?;
+    Ok(lines)
+}
+
+fn list(dir: &Path, lines: &mut Vec<TerminalLine>) -> Result<()> {
+    let mut subdirs = Vec::new();
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry
+            .file_name()
+            .into_string()
+            .map_err(|name| anyhow!("{:?} is not valid unicode", name))?;
+        if name.contains(char::is_whitespace) {
+            Err(anyhow!(
+                "{:?} in {} can't be listed, it has whitespace",
+                name,
+                dir.display()
+            ))?;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            entries.push(TerminalLine::Dir(name.clone()));
+            subdirs.push(name);
+        } else {
+            // The entry's own metadata, so symlinks aren't followed.
+            entries.push(TerminalLine::File(entry.metadata()?.len() as usize, name));
+        }
+    }
+
+    lines.push(TerminalLine::LS);
+    lines.extend(entries.into_iter().sorted_by_key(|line| match line {
+        TerminalLine::Dir(name) | TerminalLine::File(_, name) => name.clone(),
+        _ => unreachable!(),
+    }));
+
+    for name in subdirs.into_iter().sorted() {
+        lines.push(TerminalLine::CD(name.clone()));
+        list(&dir.join(name), lines)?;
+        lines.push(TerminalLine::CD("..".to_string()));
+    }
+    Ok(())
+}
+
+/// Compares the sizes read from a transcript of `dir` with what `du` makes of
+/// it, returning a line for each path they disagree on.
+///
+/// `du` counts the space taken by the directories themselves as well as their
+/// files, so that is added to the tree's sizes before comparing. Hard links
+/// are counted every time they are listed, as in the transcript. Apparent
+/// sizes in bytes need GNU `du`, so any other is an error.
+pub fn du_mismatches(dir: impl AsRef<Path>) -> Result<Vec<String>> {
+    let dir = dir.as_ref();
+    let tree = Tree::from_transcript(&transcript(dir)?)?;
+
+    let is_gnu = Command::new("du")
+        .arg("--version")
+        .output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).contains("GNU coreutils"))
+        .unwrap_or(false);
+    if !is_gnu {
+        Err(anyhow!(
+            "Comparing with du needs GNU du, which wasn't found"
+        ))?;
+    }
+
+    let output = Command::new("du")
+        .args([
+            "--all",
+            "--apparent-size",
+            "--block-size=1",
+            "--count-links",
+        ])
+        .arg(dir)
+        .output()?;
+    if !output.status.success() {
+        Err(anyhow!(
+            "du failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))?;
+    }
+
+    let du = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| {
+            let (size, path) = line
+                .split_once('\t')
+                .ok_or(anyhow!("Unexpected du output: {}", line))?;
+            let path = Path::new(path).strip_prefix(dir)?.to_string_lossy();
+            Ok((format!("/{}", path), size.parse::<usize>()?))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    let own_sizes = tree
+        .ids()
+        .map(|id| match tree.node(id).kind {
+            Kind::Dir(_) => {
+                let path = dir.join(tree.segments(id).join("/"));
+                Ok(fs::metadata(path)?.len() as usize)
+            }
+            Kind::File(size) => Ok(size),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let expected = tree.roll_up(own_sizes);
+
+    let mut mismatches = Vec::new();
+    for id in tree.ids() {
+        let path = tree.path(id);
+        match du.get(&path) {
+            Some(&size) if size == expected[id] => {}
+            Some(&size) => mismatches.push(format!(
+                "{}: du says {}, the transcript {}",
+                path, size, expected[id]
+            )),
+            None => mismatches.push(format!("{}: missing from du", path)),
+        }
+    }
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example;
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let dir = export_to_temp(&tree).unwrap();
+
+        let lines = transcript(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let lines = lines.unwrap();
+        let read_back = Tree::from_transcript(&lines).unwrap();
+        assert_eq!(read_back.to_string(), tree.to_string());
+        assert_eq!(lines[..4], example()[..4]);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn matches_du() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let dir = export_to_temp(&tree).unwrap();
+        let mismatches = du_mismatches(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(mismatches.unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn links() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let dir = export_to_temp(&tree).unwrap();
+        std::os::unix::fs::symlink("a/e/i", dir.join("link")).unwrap();
+        std::os::unix::fs::symlink("d", dir.join("dirlink")).unwrap();
+        fs::hard_link(dir.join("b.txt"), dir.join("a/hard")).unwrap();
+
+        let lines = transcript(&dir);
+        let mismatches = du_mismatches(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let read_back = Tree::from_transcript(&lines.unwrap()).unwrap();
+        let size = |path| match read_back.node(read_back.lookup(path).unwrap()).kind {
+            Kind::File(size) => size,
+            Kind::Dir(_) => panic!("{} is a directory", path),
+        };
+        assert_eq!(size("/link"), "a/e/i".len());
+        assert_eq!(size("/dirlink"), 1);
+        assert_eq!(size("/a/hard"), 14848514);
+        assert_eq!(mismatches.unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn no_overwriting() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let dir = export_to_temp(&tree).unwrap();
+        let again = export(&tree, &dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(again.is_err());
+    }
+
+    #[test]
+    fn whitespace_names() {
+        let mut tree = Tree::new();
+        tree.add_file(Tree::ROOT, "a b", 1).unwrap();
+        let dir = export_to_temp(&tree).unwrap();
+        let result = transcript(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+use super::tree::{NodeId, Tree};
+use super::TerminalLine;
+
+/// Something odd in a transcript that didn't stop it being read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+/// An entry of an `ls`, `None` for a directory or the size of a file.
+type Listing = BTreeMap<String, Option<usize>>;
+
+/// Rebuilds a tree from a transcript, warning about what doesn't add up.
+///
+/// - `cd` takes absolute and relative paths of any length, `cd ..` at the
+///   root stays there and going into a directory no `ls` showed is warned
+///   about.
+/// - Listing a directory again doesn't count its files twice, but any
+///   difference from the earlier listing is warned about.
+/// - Output with no `ls` before it is still added to the tree, with a warning.
+#[derive(Debug, Clone)]
+pub struct Interpreter {
+    pub tree: Tree,
+    pub warnings: Vec<Warning>,
+    cwd: NodeId,
+    /// The directory being listed and what has been listed so far.
+    listing: Option<(NodeId, usize, Listing)>,
+    /// The most recent complete listing of each directory, and its line.
+    listed: HashMap<NodeId, (usize, Listing)>,
+    /// Directories that showed up in some `ls`.
+    seen: HashSet<NodeId>,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Interpreter {
+            tree: Tree::new(),
+            warnings: Vec::new(),
+            cwd: Tree::ROOT,
+            listing: None,
+            listed: HashMap::new(),
+            seen: HashSet::from([Tree::ROOT]),
+        }
+    }
+
+    pub fn run(lines: &[TerminalLine]) -> Result<Self> {
+        let mut interpreter = Interpreter::new();
+        for (idx, line) in lines.iter().enumerate() {
+            interpreter
+                .step(idx + 1, line)
+                .map_err(|e| anyhow!("Line {}: {}", idx + 1, e))?;
+        }
+        interpreter.finish_listing();
+        Ok(interpreter)
+    }
+
+    fn warn(&mut self, line: usize, message: String) {
+        self.warnings.push(Warning { line, message });
+    }
+
+    /// Handles line number `line`.
+    pub fn step(&mut self, line: usize, terminal_line: &TerminalLine) -> Result<()> {
+        match terminal_line {
+            TerminalLine::CD(dest) => {
+                self.finish_listing();
+                self.cd(line, dest)?;
+            }
+            TerminalLine::LS => {
+                self.finish_listing();
+                self.listing = Some((self.cwd, line, Listing::new()));
+            }
+            TerminalLine::Dir(name) => {
+                let dir = self.tree.mkdir(self.cwd, name)?;
+                self.seen.insert(dir);
+                self.record(line, name, None);
+            }
+            TerminalLine::File(size, name) => {
+                self.tree.add_file(self.cwd, name, *size)?;
+                self.record(line, name, Some(*size));
+            }
+        }
+        Ok(())
+    }
+
+    fn cd(&mut self, line: usize, dest: &str) -> Result<()> {
+        if dest.starts_with('/') {
+            self.cwd = Tree::ROOT;
+        }
+
+        for name in dest.split('/') {
+            match name {
+                "" | "." => {}
+                ".." => match self.tree.node(self.cwd).parent {
+                    Some(parent) => self.cwd = parent,
+                    None => self.warn(line, "cd .. at the root stays at the root".to_string()),
+                },
+                name => {
+                    self.cwd = self.tree.mkdir(self.cwd, name)?;
+                    if !self.seen.contains(&self.cwd) {
+                        let path = self.tree.path(self.cwd);
+                        self.warn(line, format!("cd into {}, which no ls has shown", path));
+                        // Only warn the first time.
+                        self.seen.insert(self.cwd);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, line: usize, name: &str, entry: Option<usize>) {
+        match &mut self.listing {
+            Some((dir, _, listing)) if *dir == self.cwd => {
+                listing.insert(name.to_string(), entry);
+            }
+            _ => self.warn(line, format!("{} is listed without an ls", name)),
+        }
+    }
+
+    /// Compares a listing that just ended with the previous one of the same
+    /// directory.
+    fn finish_listing(&mut self) {
+        let Some((dir, line, listing)) = self.listing.take() else {
+            return;
+        };
+
+        if let Some((before_line, before)) = self.listed.get(&dir) {
+            let path = self.tree.path(dir);
+            let describe = |entry: &Option<usize>| match entry {
+                None => "a directory".to_string(),
+                Some(size) => format!("{} bytes", size),
+            };
+
+            let mut messages = Vec::new();
+            for (name, entry) in before {
+                match listing.get(name) {
+                    None => messages.push(format!(
+                        "ls of {} no longer shows {}, which the ls on line {} did",
+                        path, name, before_line
+                    )),
+                    Some(now) if now != entry => messages.push(format!(
+                        "ls of {} shows {} as {}, the ls on line {} as {}",
+                        path,
+                        name,
+                        describe(now),
+                        before_line,
+                        describe(entry)
+                    )),
+                    _ => {}
+                }
+            }
+            for name in listing.keys().filter(|name| !before.contains_key(*name)) {
+                messages.push(format!(
+                    "ls of {} shows {}, which the ls on line {} didn't",
+                    path, name, before_line
+                ));
+            }
+            for message in messages {
+                self.warn(line, message);
+            }
+        }
+        self.listed.insert(dir, (line, listing));
+    }
+}
+
+impl Tree {
+    /// Replays the `cd`s and `ls`s of a terminal session, see `Interpreter`
+    /// for the warnings this ignores.
+    pub fn from_transcript(lines: &[TerminalLine]) -> Result<Self> {
+        Ok(Interpreter::run(lines)?.tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example;
+    use super::*;
+    use itertools::Itertools;
+
+    fn report(warnings: &[Warning]) -> String {
+        warnings.iter().join("\n")
+    }
+
+    fn run(transcript: &str) -> Interpreter {
+        let lines = transcript
+            .lines()
+            .map(|line| line.parse().unwrap())
+            .collect_vec();
+        Interpreter::run(&lines).unwrap()
+    }
+
+    #[test]
+    fn example_is_clean() {
+        let interpreter = Interpreter::run(&example()).unwrap();
+        assert!(interpreter.warnings.is_empty());
+        assert_eq!(interpreter.tree.sizes()[Tree::ROOT], 48381165);
+    }
+
+    #[test]
+    fn repeated_ls() {
+        let interpreter = run("$ cd /\n$ ls\n10 a\ndir b\n$ ls\n10 a\ndir b");
+        assert_eq!(interpreter.tree.sizes()[Tree::ROOT], 10);
+        assert!(interpreter.warnings.is_empty());
+
+        let interpreter = run("$ cd /\n$ ls\n10 a\ndir b\n$ ls\n20 a\n5 c");
+        assert_eq!(interpreter.tree.sizes()[Tree::ROOT], 25);
+        assert_eq!(
+            report(&interpreter.warnings),
+            "Line 5: ls of / shows a as 20 bytes, the ls on line 2 as 10 bytes\n\
+             Line 5: ls of / no longer shows b, which the ls on line 2 did\n\
+             Line 5: ls of / shows c, which the ls on line 2 didn't"
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let interpreter = run("$ ls\ndir a\n2 y\n$ cd a\n$ ls\ndir b\n$ cd b\n$ ls\n1 x\n\
+             $ cd /\n$ ls\ndir a\n2 y\n$ cd /a/b\n$ ls\n1 x\n$ cd ../..\n$ cd ..\n$ cd a/./b");
+        let tree = &interpreter.tree;
+        assert_eq!(interpreter.cwd, tree.lookup("/a/b").unwrap());
+        assert_eq!(tree.sizes()[Tree::ROOT], 3);
+        assert_eq!(
+            report(&interpreter.warnings),
+            "Line 18: cd .. at the root stays at the root"
+        );
+    }
+
+    #[test]
+    fn unseen_dirs() {
+        let interpreter = run("$ cd /\n$ cd a\n$ ls\n1 x\n$ cd ..\n$ cd a\n4 y");
+        assert_eq!(
+            report(&interpreter.warnings),
+            "Line 2: cd into /a, which no ls has shown\nLine 7: y is listed without an ls"
+        );
+        assert_eq!(interpreter.tree.sizes()[Tree::ROOT], 5);
+    }
+
+    #[test]
+    fn conflicts() {
+        let lines = ["$ ls", "1 a", "$ cd a"].map(|line| line.parse().unwrap());
+        assert_eq!(
+            Interpreter::run(&lines).unwrap_err().to_string(),
+            "Line 3: /a is a file, not a directory"
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+use super::tree::{Kind, NodeId, Tree};
+
+/// The size of the disk and how much of it has to be free.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Disk {
+    pub total: usize,
+    pub min_unused: usize,
+}
+
+impl Disk {
+    pub const PUZZLE: Disk = Disk {
+        total: 70000000,
+        min_unused: 30000000,
+    };
+
+    /// How much has to be deleted from `tree` to leave enough space free.
+    pub fn to_free(&self, tree: &Tree) -> Result<usize> {
+        let used = tree.sizes()[Tree::ROOT];
+        let unused = self.total.checked_sub(used).ok_or(anyhow!(
+            "{} bytes are used, more than the {} on the disk",
+            used,
+            self.total
+        ))?;
+        Ok(self.min_unused.saturating_sub(unused))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Delete as few bytes as possible, deleting whole directories where all
+    /// of their files go.
+    FewestBytes,
+    /// Make as few deletions as possible, then delete as few bytes as that
+    /// allows. One deletion always does, the root being a candidate.
+    FewestDeletions,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deletion {
+    pub id: NodeId,
+    pub path: String,
+    pub size: usize,
+    pub is_dir: bool,
+}
+
+/// Directories and files to delete, none of them inside another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub target: usize,
+    pub freed: usize,
+    pub deletions: Vec<Deletion>,
+}
+
+/// Plans how to free at least `target` bytes from `tree`.
+pub fn plan(tree: &Tree, target: usize, objective: Objective) -> Result<Plan> {
+    let sizes = tree.sizes();
+    if target > sizes[Tree::ROOT] {
+        Err(anyhow!(
+            "Can't free {} bytes, there are only {}",
+            target,
+            sizes[Tree::ROOT]
+        ))?;
+    }
+
+    let chosen = match objective {
+        _ if target == 0 => Vec::new(),
+        Objective::FewestDeletions => tree
+            .ids()
+            .filter(|&id| sizes[id] >= target)
+            .min_by_key(|&id| sizes[id])
+            .into_iter()
+            .collect(),
+        Objective::FewestBytes => collapse(tree, &fewest_bytes(tree, target)?),
+    };
+
+    let deletions = chosen
+        .into_iter()
+        .map(|id| Deletion {
+            id,
+            path: tree.path(id),
+            size: sizes[id],
+            is_dir: tree.node(id).is_dir(),
+        })
+        .sorted_by_key(|deletion| deletion.path.clone())
+        .collect_vec();
+
+    Ok(Plan {
+        target,
+        freed: deletions.iter().map(|deletion| deletion.size).sum(),
+        deletions,
+    })
+}
+
+/// The most totals below the target `fewest_bytes` keeps track of before
+/// giving up.
+pub const MAX_TOTALS: usize = 1 << 20;
+
+/// The files adding up to the smallest total of at least `target`.
+///
+/// Deleting a directory frees the same bytes as deleting all of its files, so
+/// only files need choosing. This is subset sum over the file sizes, tracking
+/// just the totals reachable so far, so memory goes with how many different
+/// totals there are rather than how many bytes. Totals of at least `target`
+/// are never grown further, only the smallest is kept. Errors when there are
+/// more than `MAX_TOTALS` totals below `target`.
+fn fewest_bytes(tree: &Tree, target: usize) -> Result<Vec<NodeId>> {
+    let files = tree
+        .ids()
+        .filter_map(|id| match tree.node(id).kind {
+            Kind::File(size) if size > 0 => Some((id, size)),
+            _ => None,
+        })
+        .collect_vec();
+
+    // Each total below `target` with the file whose addition first reached it.
+    let mut reached: BTreeMap<usize, usize> = BTreeMap::from([(0, usize::MAX)]);
+    // The smallest total of at least `target` and the file that reached it.
+    let mut best: Option<(usize, usize)> = None;
+
+    for (idx, &(_, size)) in files.iter().enumerate() {
+        let new = reached
+            .keys()
+            .map(|&total| total.saturating_add(size))
+            .filter(|total| !reached.contains_key(total))
+            .collect_vec();
+        for total in new {
+            if total < target {
+                reached.insert(total, idx);
+                if reached.len() > MAX_TOTALS {
+                    Err(anyhow!(
+                        "The files add up to more than {} totals below {}, too many to find the fewest bytes",
+                        MAX_TOTALS,
+                        target
+                    ))?;
+                }
+            } else if best.is_none_or(|(best, _)| total < best) {
+                best = Some((total, idx));
+            }
+        }
+        if best.is_some_and(|(best, _)| best == target) {
+            break;
+        }
+    }
+
+    let Some((mut total, idx)) = best else {
+        return Ok(Vec::new());
+    };
+
+    // Each total was first reached by adding a file to a total reachable with
+    // earlier files only, so stepping back never uses a file twice.
+    let mut chosen = Vec::new();
+    let mut idx = idx;
+    loop {
+        let (id, size) = files[idx];
+        chosen.push(id);
+        total -= size;
+        if total == 0 {
+            return Ok(chosen);
+        }
+        idx = reached[&total];
+    }
+}
+
+/// The fewest directories and files deleting exactly `files`: every directory
+/// all of whose files are chosen is deleted whole.
+fn collapse(tree: &Tree, files: &[NodeId]) -> Vec<NodeId> {
+    let mut chosen = vec![0; tree.ids().count()];
+    for &id in files {
+        chosen[id] = 1;
+    }
+    let chosen = tree.roll_up(chosen);
+    let counts = tree.file_counts();
+    let whole = |id: NodeId| counts[id] > 0 && chosen[id] == counts[id];
+
+    let mut deletions = Vec::new();
+    let mut stack = vec![Tree::ROOT];
+    while let Some(id) = stack.pop() {
+        if whole(id) {
+            deletions.push(id);
+        } else {
+            stack.extend(tree.children(id).filter(|&child| chosen[child] > 0));
+        }
+    }
+    deletions
+}
+
+impl Display for Plan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for deletion in &self.deletions {
+            let kind = if deletion.is_dir { "dir" } else { "file" };
+            writeln!(f, "rm {:>4} {:>10} {}", kind, deletion.size, deletion.path)?;
+        }
+        write!(
+            f,
+            "{} deletions free {} bytes, {} more than the {} needed",
+            self.deletions.len(),
+            self.freed,
+            self.freed - self.target,
+            self.target
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example;
+    use super::*;
+
+    fn paths(plan: &Plan) -> Vec<&str> {
+        plan.deletions
+            .iter()
+            .map(|deletion| deletion.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn example_plans() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let target = Disk::PUZZLE.to_free(&tree).unwrap();
+        assert_eq!(target, 8381165);
+
+        // A file can do better than the smallest large enough directory, /d.
+        for objective in [Objective::FewestDeletions, Objective::FewestBytes] {
+            let plan = plan(&tree, target, objective).unwrap();
+            assert_eq!(paths(&plan), vec!["/c.dat"]);
+            assert_eq!(plan.freed, 8504156);
+        }
+
+        let plan = plan(&tree, 8504157, Objective::FewestDeletions).unwrap();
+        assert_eq!(paths(&plan), vec!["/b.txt"]);
+        let plan = super::plan(&tree, 8504157, Objective::FewestBytes).unwrap();
+        assert_eq!(paths(&plan), vec!["/a/e", "/c.dat"]);
+        assert_eq!(plan.freed, 8504740);
+    }
+
+    #[test]
+    fn whole_directories() {
+        let mut tree = Tree::new();
+        let a = tree.mkdir(Tree::ROOT, "a").unwrap();
+        let b = tree.mkdir(a, "b").unwrap();
+        tree.add_file(a, "x", 5).unwrap();
+        tree.add_file(b, "y", 3).unwrap();
+        tree.add_file(b, "z", 4).unwrap();
+        tree.add_file(Tree::ROOT, "w", 9).unwrap();
+
+        let fewest = |target| {
+            let plan = plan(&tree, target, Objective::FewestBytes).unwrap();
+            (paths(&plan).join(" "), plan.freed)
+        };
+        assert_eq!(fewest(7), ("/a/b".to_string(), 7));
+        assert_eq!(fewest(8), ("/a/b/y /a/x".to_string(), 8));
+        assert_eq!(fewest(12), ("/a".to_string(), 12));
+        assert_eq!(fewest(14), ("/a/x /w".to_string(), 14));
+        assert_eq!(fewest(18), ("/a/b/z /a/x /w".to_string(), 18));
+        assert_eq!(fewest(21), ("/".to_string(), 21));
+        assert_eq!(fewest(0), (String::new(), 0));
+        assert!(plan(&tree, 22, Objective::FewestBytes).is_err());
+    }
+
+    #[test]
+    fn large_files() {
+        const GB: usize = 1 << 30;
+        let mut tree = Tree::new();
+        let a = tree.mkdir(Tree::ROOT, "a").unwrap();
+        tree.add_file(a, "x", 3 * GB).unwrap();
+        tree.add_file(a, "y", 5 * GB).unwrap();
+        tree.add_file(Tree::ROOT, "z", 7 * GB).unwrap();
+        tree.add_file(Tree::ROOT, "w", 2 * GB + 1).unwrap();
+
+        let plan = plan(&tree, 9 * GB, Objective::FewestBytes).unwrap();
+        assert_eq!(paths(&plan), vec!["/w", "/z"]);
+        assert_eq!(plan.freed, 9 * GB + 1);
+        let plan = super::plan(&tree, 8 * GB, Objective::FewestBytes).unwrap();
+        assert_eq!(paths(&plan), vec!["/a"]);
+    }
+
+    #[test]
+    fn too_many_totals() {
+        let mut tree = Tree::new();
+        for bit in 0..24 {
+            tree.add_file(Tree::ROOT, &bit.to_string(), 1 << bit)
+                .unwrap();
+        }
+        assert!(plan(&tree, (1 << 24) - 1, Objective::FewestBytes).is_err());
+        assert!(plan(&tree, (1 << 24) - 1, Objective::FewestDeletions).is_ok());
+    }
+
+    #[test]
+    fn disk() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let disk = Disk {
+            total: 50000000,
+            min_unused: 1000000,
+        };
+        assert_eq!(disk.to_free(&tree).unwrap(), 0);
+        let disk = Disk {
+            total: 40000000,
+            ..disk
+        };
+        assert!(disk.to_free(&tree).is_err());
+    }
+
+    #[test]
+    fn render() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let plan = plan(&tree, 8381165, Objective::FewestBytes).unwrap();
+        assert_eq!(
+            plan.to_string(),
+            "rm file    8504156 /c.dat\n1 deletions free 8504156 bytes, 122991 more than the 8381165 needed"
+        );
+    }
+}
@@ -0,0 +1,384 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Kind {
+    Dir(BTreeMap<String, NodeId>),
+    File(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: Kind,
+}
+
+impl Node {
+    pub fn is_dir(&self) -> bool {
+        matches!(self.kind, Kind::Dir(_))
+    }
+}
+
+/// A directory tree, every node kept in one list and referring to the others
+/// by their index in it. Nodes are only ever added after their parent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tree {
+    nodes: Vec<Node>,
+}
+
+/// How much space a directory takes up, as listed by `Tree::du`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    pub id: NodeId,
+    pub path: String,
+    pub size: usize,
+    /// Files anywhere below the directory.
+    pub files: usize,
+}
+
+impl Default for Tree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tree {
+    pub const ROOT: NodeId = 0;
+
+    pub fn new() -> Self {
+        Tree {
+            nodes: vec![Node {
+                name: "/".to_string(),
+                parent: None,
+                kind: Kind::Dir(BTreeMap::new()),
+            }],
+        }
+    }
+
+    pub fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id]
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> {
+        0..self.nodes.len()
+    }
+
+    pub fn dirs(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.ids().filter(|&id| self.nodes[id].is_dir())
+    }
+
+    pub fn children(&self, dir: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        match &self.nodes[dir].kind {
+            Kind::Dir(children) => Some(children.values().copied()),
+            Kind::File(_) => None,
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        match &self.nodes[dir].kind {
+            Kind::Dir(children) => children.get(name).copied(),
+            Kind::File(_) => None,
+        }
+    }
+
+    /// Names are single path components, so a node never ends up outside its
+    /// parent when the tree is written to disk.
+    fn add(&mut self, parent: NodeId, name: &str, kind: Kind) -> Result<NodeId> {
+        if matches!(name, "" | "." | "..") || name.contains('/') {
+            Err(anyhow!(
+                "{:?} can't be the name of a file or directory",
+                name
+            ))?;
+        }
+        let id = self.nodes.len();
+        match &mut self.nodes[parent].kind {
+            Kind::Dir(children) => children.insert(name.to_string(), id),
+            Kind::File(_) => Err(anyhow!("{} is not a directory", self.path(parent)))?,
+        };
+        self.nodes.push(Node {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+        Ok(id)
+    }
+
+    /// The directory `name` in `parent`, made if it isn't there yet.
+    pub fn mkdir(&mut self, parent: NodeId, name: &str) -> Result<NodeId> {
+        match self.child(parent, name) {
+            Some(id) if self.nodes[id].is_dir() => Ok(id),
+            Some(id) => Err(anyhow!("{} is a file, not a directory", self.path(id))),
+            None => self.add(parent, name, Kind::Dir(BTreeMap::new())),
+        }
+    }
+
+    /// Adds the file `name` to `parent`, or sets its size if it is there.
+    pub fn add_file(&mut self, parent: NodeId, name: &str, size: usize) -> Result<NodeId> {
+        match self.child(parent, name) {
+            Some(id) if self.nodes[id].is_dir() => {
+                Err(anyhow!("{} is a directory, not a file", self.path(id)))
+            }
+            Some(id) => {
+                self.nodes[id].kind = Kind::File(size);
+                Ok(id)
+            }
+            None => self.add(parent, name, Kind::File(size)),
+        }
+    }
+
+    /// The names from the root down to `id`, not including the root.
+    pub fn segments(&self, mut id: NodeId) -> Vec<&str> {
+        let mut segments = Vec::new();
+        while let Some(parent) = self.nodes[id].parent {
+            segments.push(self.nodes[id].name.as_str());
+            id = parent;
+        }
+        segments.reverse();
+        segments
+    }
+
+    pub fn path(&self, id: NodeId) -> String {
+        format!("/{}", self.segments(id).join("/"))
+    }
+
+    /// The node at an absolute path such as `/a/e`.
+    pub fn lookup(&self, path: &str) -> Option<NodeId> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(Tree::ROOT, |dir, name| self.child(dir, name))
+    }
+
+    /// The size of every node, a directory's being the total of all the files
+    /// below it.
+    pub fn sizes(&self) -> Vec<usize> {
+        self.roll_up(
+            self.nodes
+                .iter()
+                .map(|node| match node.kind {
+                    Kind::File(size) => size,
+                    Kind::Dir(_) => 0,
+                })
+                .collect(),
+        )
+    }
+
+    /// How many files are at or below every node.
+    pub fn file_counts(&self) -> Vec<usize> {
+        self.roll_up(
+            self.nodes
+                .iter()
+                .map(|node| match node.kind {
+                    Kind::File(_) => 1,
+                    Kind::Dir(_) => 0,
+                })
+                .collect(),
+        )
+    }
+
+    /// Totals `values`, one for each node, over every node and its
+    /// descendants. Children come after their parent, so a single pass from
+    /// the back adds each node into its parent after all of its own children
+    /// were added into it.
+    pub(crate) fn roll_up(&self, values: Vec<usize>) -> Vec<usize> {
+        let mut totals = values;
+        for id in (1..self.nodes.len()).rev() {
+            if let Some(parent) = self.nodes[id].parent {
+                totals[parent] += totals[id];
+            }
+        }
+        totals
+    }
+
+    /// Every directory with its total size, largest first.
+    pub fn du(&self) -> Vec<Usage> {
+        let sizes = self.sizes();
+        let files = self.file_counts();
+
+        self.dirs()
+            .map(|id| Usage {
+                id,
+                path: self.path(id),
+                size: sizes[id],
+                files: files[id],
+            })
+            .sorted_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)))
+            .collect()
+    }
+
+    /// The nodes whose path matches `pattern`, in which `*` and `?` match
+    /// within a name and `**` matches any number of directories. Patterns not
+    /// starting with `/` match anywhere in the tree.
+    pub fn glob(&self, pattern: &str) -> Vec<NodeId> {
+        let pattern = match pattern.strip_prefix('/') {
+            Some(pattern) => pattern.split('/').collect_vec(),
+            None => ["**"].into_iter().chain(pattern.split('/')).collect_vec(),
+        };
+        let pattern = pattern
+            .into_iter()
+            .filter(|segment| !segment.is_empty())
+            .collect_vec();
+
+        self.ids()
+            .filter(|&id| glob_matches(&pattern, &self.segments(id)))
+            .collect()
+    }
+}
+
+fn glob_matches(pattern: &[&str], path: &[&str]) -> bool {
+    match (pattern.split_first(), path.split_first()) {
+        (None, _) => path.is_empty(),
+        (Some((&"**", rest)), _) => {
+            glob_matches(rest, path) || (!path.is_empty() && glob_matches(pattern, &path[1..]))
+        }
+        (Some(_), None) => false,
+        (Some((segment, rest)), Some((name, path))) => {
+            name_matches(segment.as_bytes(), name.as_bytes()) && glob_matches(rest, path)
+        }
+    }
+}
+
+fn name_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, _) => name.is_empty(),
+        (Some((b'*', rest)), _) => {
+            name_matches(rest, name) || (!name.is_empty() && name_matches(pattern, &name[1..]))
+        }
+        (Some(_), None) => false,
+        (Some((&p, rest)), Some((&c, name))) => (p == b'?' || p == c) && name_matches(rest, name),
+    }
+}
+
+impl Display for Tree {
+    /// Draws the tree as in the puzzle, children sorted by name.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut stack = vec![(Tree::ROOT, 0)];
+        while let Some((id, depth)) = stack.pop() {
+            let node = &self.nodes[id];
+            let indent = "  ".repeat(depth);
+            match node.kind {
+                Kind::Dir(_) => writeln!(f, "{}- {} (dir)", indent, node.name)?,
+                Kind::File(size) => writeln!(f, "{}- {} (file, size={})", indent, node.name, size)?,
+            }
+            let children = self.children(id).collect_vec();
+            stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+}
+
+impl Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:>10} {:>6} {}", self.size, self.files, self.path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::example;
+    use super::*;
+
+    #[test]
+    fn invalid_names() {
+        let mut tree = Tree::new();
+        for name in ["", ".", "..", "a/b", "../x"] {
+            assert!(tree.mkdir(Tree::ROOT, name).is_err(), "{:?}", name);
+            assert!(tree.add_file(Tree::ROOT, name, 1).is_err(), "{:?}", name);
+        }
+        assert!(tree.add_file(Tree::ROOT, "..x", 1).is_ok());
+        assert_eq!(tree.ids().count(), 2);
+
+        let lines = ["$ cd /", "$ ls", "123 ../../x"].map(|line| line.parse().unwrap());
+        assert_eq!(
+            Tree::from_transcript(&lines).unwrap_err().to_string(),
+            "Line 3: \"../../x\" can't be the name of a file or directory"
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let sizes = tree.sizes();
+        assert_eq!(sizes[tree.lookup("/a/e").unwrap()], 584);
+        assert_eq!(sizes[tree.lookup("/a").unwrap()], 94853);
+        assert_eq!(sizes[tree.lookup("/d").unwrap()], 24933642);
+        assert_eq!(sizes[Tree::ROOT], 48381165);
+        assert_eq!(tree.file_counts()[Tree::ROOT], 10);
+        assert_eq!(tree.lookup("/a/x"), None);
+    }
+
+    #[test]
+    fn du() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let du = tree.du();
+        assert_eq!(
+            du.iter().map(|u| u.path.as_str()).collect_vec(),
+            vec!["/", "/d", "/a", "/a/e"]
+        );
+        assert_eq!(du[2].files, 4);
+        assert_eq!(du[3].to_string(), "       584      1 /a/e");
+
+        // The two puzzle parts.
+        let small = du.iter().filter(|u| u.size <= 100000).map(|u| u.size);
+        assert_eq!(small.sum::<usize>(), 95437);
+        let to_free = 30000000 - (70000000 - du[0].size);
+        let freed = du.iter().filter(|u| u.size >= to_free).map(|u| u.size);
+        assert_eq!(freed.min(), Some(24933642));
+    }
+
+    #[test]
+    fn display() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let expected = "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+";
+        assert_eq!(tree.to_string(), expected);
+    }
+
+    #[test]
+    fn glob() {
+        let tree = Tree::from_transcript(&example()).unwrap();
+        let paths = |pattern| {
+            tree.glob(pattern)
+                .into_iter()
+                .map(|id| tree.path(id))
+                .sorted()
+                .collect_vec()
+        };
+
+        assert_eq!(
+            paths("*.*"),
+            vec!["/a/h.lst", "/b.txt", "/c.dat", "/d/d.ext", "/d/d.log"]
+        );
+        assert_eq!(paths("/d/d.*"), vec!["/d/d.ext", "/d/d.log"]);
+        assert_eq!(paths("/*"), vec!["/a", "/b.txt", "/c.dat", "/d"]);
+        assert_eq!(
+            paths("/a/**"),
+            vec!["/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/a/h.lst"]
+        );
+        assert_eq!(
+            paths("?"),
+            vec!["/a", "/a/e", "/a/e/i", "/a/f", "/a/g", "/d", "/d/j", "/d/k"]
+        );
+        assert_eq!(paths("/"), vec!["/"]);
+    }
+}
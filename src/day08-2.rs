@@ -1,114 +1,44 @@
-use std::fmt::Display;
-use std::str::FromStr;
-
-use anyhow::Error;
+use anyhow::anyhow;
 use anyhow::Result;
 
+use aoc::day08::Grid;
+use aoc::day08::Sightlines;
+use aoc::input_file;
 use aoc::read_and_parse;
 use aoc::time_it;
 
+/// Pass `--diagonal` to also look along the diagonals, or
+/// `--viewshed ROW COL HEIGHT RANGE` to draw what can be seen from `HEIGHT`
+/// above the tree at `ROW`, `COL` within `RANGE` trees.
 fn main() -> Result<()> {
-    time_it(|| solution())?;
-    Ok(())
-}
-
-fn solution() -> Result<usize> {
-    let grid = read_and_parse::<Grid>("input/day08.txt")?;
-    let (ncols, nrows) = grid.shape();
-
-    // NOTE: edges can be ignored!
-    Ok((1..ncols - 1)
-        .map(|col| {
-            (1..nrows - 1)
-                .map(|row| grid.scenic_score(col, row))
-                .max()
-                .unwrap()
-        })
-        .max()
-        .unwrap())
-}
-
-impl Grid {
-    fn scenic_score(&self, col: usize, row: usize) -> usize {
-        let (ncols, nrows) = self.shape();
-
-        // looking up?
-        let n_up = (0..col)
-            .rev()
-            .find_map(|x| (self.data[x][row] >= self.data[col][row]).then_some(col - x))
-            .unwrap_or(col);
-
-        // looking down?
-        let n_down = (col + 1..ncols)
-            .find_map(|x| (self.data[x][row] >= self.data[col][row]).then_some(x - col))
-            .unwrap_or(ncols - col - 1);
-
-        // looking left?
-        let n_left = (0..row)
-            .rev()
-            .find_map(|x| (self.data[col][x] >= self.data[col][row]).then_some(row - x))
-            .unwrap_or(row);
-
-        // looking right?
-        let n_right = (row + 1..nrows)
-            .find_map(|x| (self.data[col][x] >= self.data[col][row]).then_some(x - row))
-            .unwrap_or(nrows - row - 1);
-
-        n_up * n_down * n_left * n_right
-    }
-}
-
-#[derive(Debug)]
-struct Grid {
-    data: Vec<Vec<usize>>,
-}
-
-impl Grid {
-    fn new(ncols: usize, nrows: usize) -> Self {
-        Grid {
-            data: vec![vec![0; nrows]; ncols],
+    let args = std::env::args().collect::<Vec<_>>();
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("--diagonal") => time_it(|| solution(Sightlines::WithDiagonals))?,
+        Some("--viewshed") => {
+            let numbers = args[2..]
+                .iter()
+                .map(|arg| arg.parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()?;
+            let [row, col, height, range] = numbers[..] else {
+                Err(anyhow!("--viewshed needs a row, column, height and range"))?
+            };
+            let grid = read_and_parse::<Grid>(input_file(8)?)?;
+            let viewshed = grid.viewshed((row, col), height, range)?;
+            print!("{}", viewshed);
+            println!("{} trees in view", viewshed.count());
         }
+        _ => time_it(|| solution(Sightlines::Axes))?,
     }
-
-    fn shape(&self) -> (usize, usize) {
-        (self.data.len(), self.data[0].len())
-    }
-
-    fn iter_rows(&self) -> impl Iterator<Item = impl Iterator<Item = &usize>> {
-        self.data.iter().map(|row| row.iter())
-    }
-
-    fn iter_cols(&self) -> impl Iterator<Item = impl Iterator<Item = &usize>> {
-        let (ncols, nrows) = self.shape();
-        (0..ncols).map(move |col| (0..nrows).map(move |row| &self.data[row][col]))
-    }
-}
-
-impl Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in self.iter_rows() {
-            let row = row.map(|x| x.to_string()).collect::<Vec<_>>().join(" ");
-            writeln!(f, "{}", row)?;
-        }
-        Ok(())
-    }
+    Ok(())
 }
 
-impl FromStr for Grid {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Grid {
-            data: s
-                .lines()
-                .map(|line| {
-                    line.chars()
-                        .map(|x| x.to_digit(10).expect("why?") as usize)
-                        .collect()
-                })
-                .collect(),
-        })
-    }
+fn solution(sightlines: Sightlines) -> Result<usize> {
+    let grid = read_and_parse::<Grid>(input_file(8)?)?;
+    let (_, score) = grid
+        .views(sightlines)
+        .most_scenic()
+        .ok_or(anyhow!("There are no trees"))?;
+    Ok(score)
 }
 
 #[cfg(test)]
@@ -116,66 +46,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn grid_new() {
-        let grid = Grid::new(3, 2);
-        assert_eq!(grid.data[0][0], 0);
-        assert_eq!(grid.data[1][0], 0);
-        assert_eq!(grid.data[2][0], 0);
-        assert_eq!(grid.data[0][1], 0);
-        assert_eq!(grid.data[1][1], 0);
-        assert_eq!(grid.data[2][1], 0);
-    }
-
-    #[test]
-    fn grid_shape() {
-        let grid = Grid::new(3, 2);
-        assert_eq!(grid.shape(), (3, 2));
-    }
-
-    #[test]
-    fn grid_from_str() {
-        let grid = Grid::from_str("012\n345").unwrap();
-        assert_eq!(grid.shape(), (2, 3));
-    }
-
-    #[test]
-    fn grid_iter_rows() {
-        let grid = Grid::from_str("01\n23").unwrap();
-        let mut rows = grid.iter_rows();
-
-        let mut row = rows.next().unwrap();
-        assert_eq!(Some(&0), row.next());
-        assert_eq!(Some(&1), row.next());
-        assert_eq!(None, row.next());
-
-        let mut row = rows.next().unwrap();
-        assert_eq!(Some(&2), row.next());
-        assert_eq!(Some(&3), row.next());
-        assert_eq!(None, row.next());
-
-        assert!(rows.next().is_none());
-    }
-
-    #[test]
-    fn grid_iter_cols() {
-        let grid = Grid::from_str("02\n13").unwrap();
-        let mut cols = grid.iter_cols();
-
-        let mut col = cols.next().unwrap();
-        assert_eq!(Some(&0), col.next());
-        assert_eq!(Some(&1), col.next());
-        assert_eq!(None, col.next());
-
-        let mut col = cols.next().unwrap();
-        assert_eq!(Some(&2), col.next());
-        assert_eq!(Some(&3), col.next());
-        assert_eq!(None, col.next());
-
-        assert!(cols.next().is_none());
+    fn sln() {
+        assert_eq!(solution(Sightlines::Axes).unwrap(), 288120);
     }
-
-    // #[test]
-    // fn sln() {
-    //     assert_eq!(solution().unwrap(), 1117448);
-    // }
 }
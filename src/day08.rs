@@ -0,0 +1,357 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+pub mod viewshed;
+
+/// Tree heights, `data[row][col]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub data: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    pub fn new(nrows: usize, ncols: usize) -> Self {
+        Grid {
+            data: vec![vec![0; ncols]; nrows],
+        }
+    }
+
+    /// The number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (
+            self.data.len(),
+            self.data.first().map_or(0, |row| row.len()),
+        )
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = impl Iterator<Item = &usize>> {
+        self.data.iter().map(|row| row.iter())
+    }
+
+    pub fn iter_cols(&self) -> impl Iterator<Item = impl Iterator<Item = &usize>> {
+        let (nrows, ncols) = self.shape();
+        (0..ncols).map(move |col| (0..nrows).map(move |row| &self.data[row][col]))
+    }
+
+    /// The cell one `step` on from `(row, col)`, if it is in the grid.
+    pub fn neighbour(&self, (row, col): Cell, (drow, dcol): Step) -> Option<Cell> {
+        let (nrows, ncols) = self.shape();
+        let row = row.checked_add_signed(drow).filter(|&row| row < nrows)?;
+        let col = col.checked_add_signed(dcol).filter(|&col| col < ncols)?;
+        Some((row, col))
+    }
+
+    /// Which trees can be seen from outside the grid and how scenic each is,
+    /// looking along `sightlines`.
+    ///
+    /// Every line of trees is swept once per direction, keeping a stack of
+    /// the trees not yet hidden behind a taller or equal one. Those the
+    /// stack drops are shorter than the tree being added, so it can see past
+    /// them; the one left on top is what stops its view, and with none left
+    /// it sees all the way to the edge. That makes it O(rows × cols) per
+    /// direction.
+    pub fn views(&self, sightlines: Sightlines) -> Views {
+        let (nrows, ncols) = self.shape();
+        let mut views = Views {
+            visible: vec![vec![false; ncols]; nrows],
+            scenic: vec![vec![1; ncols]; nrows],
+        };
+
+        for &step in sightlines.steps() {
+            let back = (-step.0, -step.1);
+            // Lines start at the trees looking straight out of the grid.
+            let starts = (0..nrows)
+                .flat_map(|row| (0..ncols).map(move |col| (row, col)))
+                .filter(|&cell| self.neighbour(cell, step).is_none());
+
+            for start in starts {
+                let mut stack: Vec<(usize, usize)> = Vec::new();
+                let mut cell = Some(start);
+                let mut idx = 0;
+                while let Some((row, col)) = cell {
+                    let height = self.data[row][col];
+                    while stack.last().is_some_and(|&(_, other)| other < height) {
+                        stack.pop();
+                    }
+
+                    let distance = match stack.last() {
+                        Some(&(blocker, _)) => idx - blocker,
+                        None => {
+                            views.visible[row][col] = true;
+                            idx
+                        }
+                    };
+                    views.scenic[row][col] *= distance;
+
+                    stack.push((idx, height));
+                    cell = self.neighbour((row, col), back);
+                    idx += 1;
+                }
+            }
+        }
+        views
+    }
+}
+
+/// A `(row, col)` position in a grid.
+pub type Cell = (usize, usize);
+
+/// A `(row, col)` direction to move in.
+pub type Step = (isize, isize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sightlines {
+    /// Up, down, left and right, as in the puzzle.
+    Axes,
+    /// The diagonals as well.
+    WithDiagonals,
+}
+
+impl Sightlines {
+    const STEPS: [Step; 8] = [
+        (-1, 0),
+        (1, 0),
+        (0, -1),
+        (0, 1),
+        (-1, -1),
+        (-1, 1),
+        (1, -1),
+        (1, 1),
+    ];
+
+    pub fn steps(&self) -> &'static [Step] {
+        match self {
+            Sightlines::Axes => &Self::STEPS[..4],
+            Sightlines::WithDiagonals => &Self::STEPS,
+        }
+    }
+}
+
+/// What `Grid::views` found out about every tree, indexed like the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Views {
+    /// Whether the tree can be seen from outside along some sightline.
+    pub visible: Vec<Vec<bool>>,
+    /// The product of how many trees it can see along each sightline.
+    pub scenic: Vec<Vec<usize>>,
+}
+
+impl Views {
+    pub fn visible_count(&self) -> usize {
+        self.visible
+            .iter()
+            .flatten()
+            .filter(|&&visible| visible)
+            .count()
+    }
+
+    /// The most scenic tree and its score.
+    pub fn most_scenic(&self) -> Option<(Cell, usize)> {
+        self.scenic
+            .iter()
+            .enumerate()
+            .flat_map(|(row, scores)| {
+                scores
+                    .iter()
+                    .enumerate()
+                    .map(move |(col, &score)| ((row, col), score))
+            })
+            .max_by_key(|&(_, score)| score)
+    }
+}
+
+impl Display for Views {
+    /// Draws the visible trees as `#` and the hidden ones as `.`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.visible {
+            let row = row
+                .iter()
+                .map(|&visible| if visible { '#' } else { '.' })
+                .collect::<String>();
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.iter_rows() {
+            let row = row.map(|x| x.to_string()).collect::<Vec<_>>().join(" ");
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Grid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let data = s
+            .lines()
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(col, c)| {
+                        c.to_digit(10).map(|x| x as usize).ok_or(anyhow!(
+                            "Line {}, column {}: {:?} is not a height",
+                            row + 1,
+                            col + 1,
+                            c
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(row) = data.iter().position(|row| row.len() != data[0].len()) {
+            Err(anyhow!(
+                "Line {}: {} trees, line 1 has {}",
+                row + 1,
+                data[row].len(),
+                data[0].len()
+            ))?;
+        }
+        Ok(Grid { data })
+    }
+}
+
+#[cfg(test)]
+pub(crate) const EXAMPLE: &str = "30373
+25512
+65332
+33549
+35390
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The obvious way, looking outward from every tree.
+    fn scan(grid: &Grid, sightlines: Sightlines) -> Views {
+        let (nrows, ncols) = grid.shape();
+        let mut views = Views {
+            visible: vec![vec![false; ncols]; nrows],
+            scenic: vec![vec![1; ncols]; nrows],
+        };
+        for row in 0..nrows {
+            for col in 0..ncols {
+                let height = grid.data[row][col];
+                for &step in sightlines.steps() {
+                    let mut cell = (row, col);
+                    let mut distance = 0;
+                    let mut blocked = false;
+                    while let Some(next) = grid.neighbour(cell, step) {
+                        distance += 1;
+                        cell = next;
+                        if grid.data[next.0][next.1] >= height {
+                            blocked = true;
+                            break;
+                        }
+                    }
+                    views.visible[row][col] |= !blocked;
+                    views.scenic[row][col] *= distance;
+                }
+            }
+        }
+        views
+    }
+
+    #[test]
+    fn grid_new() {
+        let grid = Grid::new(3, 2);
+        assert_eq!(grid.data[0][0], 0);
+        assert_eq!(grid.data[1][0], 0);
+        assert_eq!(grid.data[2][0], 0);
+        assert_eq!(grid.data[0][1], 0);
+        assert_eq!(grid.data[1][1], 0);
+        assert_eq!(grid.data[2][1], 0);
+    }
+
+    #[test]
+    fn grid_shape() {
+        let grid = Grid::new(3, 2);
+        assert_eq!(grid.shape(), (3, 2));
+    }
+
+    #[test]
+    fn grid_from_str() {
+        let grid = Grid::from_str("012\n345").unwrap();
+        assert_eq!(grid.shape(), (2, 3));
+        assert_eq!(
+            Grid::from_str("012\n3x5").unwrap_err().to_string(),
+            "Line 2, column 2: 'x' is not a height"
+        );
+        assert_eq!(
+            Grid::from_str("012\n34").unwrap_err().to_string(),
+            "Line 2: 2 trees, line 1 has 3"
+        );
+    }
+
+    #[test]
+    fn grid_iter_rows() {
+        let grid = Grid::from_str("01\n23").unwrap();
+        let mut rows = grid.iter_rows();
+
+        let mut row = rows.next().unwrap();
+        assert_eq!(Some(&0), row.next());
+        assert_eq!(Some(&1), row.next());
+        assert_eq!(None, row.next());
+
+        let mut row = rows.next().unwrap();
+        assert_eq!(Some(&2), row.next());
+        assert_eq!(Some(&3), row.next());
+        assert_eq!(None, row.next());
+
+        assert!(rows.next().is_none());
+    }
+
+    #[test]
+    fn grid_iter_cols() {
+        let grid = Grid::from_str("02\n13").unwrap();
+        let mut cols = grid.iter_cols();
+
+        let mut col = cols.next().unwrap();
+        assert_eq!(Some(&0), col.next());
+        assert_eq!(Some(&1), col.next());
+        assert_eq!(None, col.next());
+
+        let mut col = cols.next().unwrap();
+        assert_eq!(Some(&2), col.next());
+        assert_eq!(Some(&3), col.next());
+        assert_eq!(None, col.next());
+
+        assert!(cols.next().is_none());
+    }
+
+    #[test]
+    fn example_views() {
+        let views = EXAMPLE.parse::<Grid>().unwrap().views(Sightlines::Axes);
+        assert_eq!(views.visible_count(), 21);
+        assert_eq!(views.most_scenic(), Some(((3, 2), 8)));
+        assert_eq!(views.scenic[1][2], 4);
+        assert_eq!(views.to_string(), "#####\n###.#\n##.##\n#.#.#\n#####\n");
+    }
+
+    #[test]
+    fn views_match_scan() {
+        let grids = [
+            EXAMPLE,
+            "1234\n5678\n9012",
+            "55555\n51115\n55555",
+            "9\n1\n9\n0",
+            "3",
+        ];
+        for grid in grids {
+            let grid = grid.parse::<Grid>().unwrap();
+            for sightlines in [Sightlines::Axes, Sightlines::WithDiagonals] {
+                assert_eq!(grid.views(sightlines), scan(&grid, sightlines));
+            }
+        }
+    }
+}
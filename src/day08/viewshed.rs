@@ -0,0 +1,194 @@
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+use super::{Cell, Grid};
+
+/// The cells an observer can see the top of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Viewshed {
+    pub observer: Cell,
+    /// Indexed like the grid.
+    pub visible: Vec<Vec<bool>>,
+}
+
+impl Viewshed {
+    pub fn count(&self) -> usize {
+        self.visible
+            .iter()
+            .flatten()
+            .filter(|&&visible| visible)
+            .count()
+    }
+}
+
+impl Grid {
+    /// What an observer `height` above the tree at `observer` can see, up to
+    /// `range` cells away.
+    ///
+    /// Rays are drawn from the observer to every cell on the edge of the
+    /// square `range` around it, clipped to the grid, with Bresenham's line
+    /// algorithm so together they pass through every cell in the square.
+    /// Going out along a ray, a tree is seen when the slope up to its top is
+    /// at least as steep as to any tree before it on the ray. A `range`
+    /// longer than the grid's diagonal is the same as the diagonal.
+    pub fn viewshed(&self, observer: Cell, height: usize, range: usize) -> Result<Viewshed> {
+        let (nrows, ncols) = self.shape();
+        let (row, col) = observer;
+        if row >= nrows || col >= ncols {
+            Err(anyhow!(
+                "({}, {}) is outside the {}x{} grid",
+                row,
+                col,
+                nrows,
+                ncols
+            ))?;
+        }
+
+        let mut visible = vec![vec![false; ncols]; nrows];
+        visible[row][col] = true;
+        let eye = self.data[row][col].saturating_add(height) as f64;
+
+        // No cell is further away than the diagonal, which is shorter than
+        // the grid's height and width put together.
+        let range = range.min(nrows + ncols);
+        let (top, bottom) = (
+            row.saturating_sub(range),
+            row.saturating_add(range).min(nrows - 1),
+        );
+        let (left, right) = (
+            col.saturating_sub(range),
+            col.saturating_add(range).min(ncols - 1),
+        );
+        let edge = (left..=right)
+            .flat_map(|col| [(top, col), (bottom, col)])
+            .chain((top..=bottom).flat_map(|row| [(row, left), (row, right)]));
+
+        for target in edge {
+            let mut steepest = f64::NEG_INFINITY;
+            for (r, c) in bresenham(observer, target).skip(1) {
+                let (drow, dcol) = (r.abs_diff(row), c.abs_diff(col));
+                if drow * drow + dcol * dcol > range * range {
+                    break;
+                }
+
+                let distance = ((drow * drow + dcol * dcol) as f64).sqrt();
+                let slope = (self.data[r][c] as f64 - eye) / distance;
+                if slope >= steepest {
+                    visible[r][c] = true;
+                    steepest = slope;
+                }
+            }
+        }
+
+        Ok(Viewshed { observer, visible })
+    }
+}
+
+/// The cells on the line from `from` to `to`, both included.
+fn bresenham(from: Cell, to: Cell) -> impl Iterator<Item = Cell> {
+    let (mut row, mut col) = (from.0 as isize, from.1 as isize);
+    let (to_row, to_col) = (to.0 as isize, to.1 as isize);
+    let (drow, dcol) = ((to_row - row).abs(), -(to_col - col).abs());
+    let (step_row, step_col) = ((to_row - row).signum(), (to_col - col).signum());
+    let mut error = drow + dcol;
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let cell = (row as usize, col as usize);
+        if (row, col) == (to_row, to_col) {
+            done = true;
+        } else {
+            let twice = 2 * error;
+            if twice >= dcol {
+                error += dcol;
+                row += step_row;
+            }
+            if twice <= drow {
+                error += drow;
+                col += step_col;
+            }
+        }
+        Some(cell)
+    })
+}
+
+impl Display for Viewshed {
+    /// Draws the observer as `@`, what it sees as `#` and the rest as `.`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (row, cells) in self.visible.iter().enumerate() {
+            let line = cells
+                .iter()
+                .enumerate()
+                .map(|(col, &visible)| match visible {
+                    _ if (row, col) == self.observer => '@',
+                    true => '#',
+                    false => '.',
+                })
+                .collect::<String>();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::EXAMPLE;
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn lines() {
+        assert_eq!(
+            bresenham((0, 0), (2, 5)).collect_vec(),
+            vec![(0, 0), (0, 1), (1, 2), (1, 3), (2, 4), (2, 5)]
+        );
+        assert_eq!(
+            bresenham((3, 1), (0, 1)).collect_vec(),
+            vec![(3, 1), (2, 1), (1, 1), (0, 1)]
+        );
+        assert_eq!(bresenham((1, 1), (1, 1)).collect_vec(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn flat_ground() {
+        let grid = Grid::new(7, 7);
+        let viewshed = grid.viewshed((3, 3), 0, 2).unwrap();
+        assert_eq!(
+            viewshed.to_string(),
+            ".......\n...#...\n..###..\n.##@##.\n..###..\n...#...\n.......\n"
+        );
+        assert_eq!(grid.viewshed((0, 0), 0, 100).unwrap().count(), 49);
+        assert_eq!(grid.viewshed((6, 6), 0, usize::MAX).unwrap().count(), 49);
+        assert_eq!(
+            grid.viewshed((3, 3), usize::MAX, usize::MAX)
+                .unwrap()
+                .count(),
+            49
+        );
+    }
+
+    #[test]
+    fn walls() {
+        let grid = "00000\n00900\n00000".parse::<Grid>().unwrap();
+        let viewshed = grid.viewshed((1, 0), 0, 10).unwrap();
+        assert_eq!(viewshed.to_string(), "#####\n@##..\n#####\n");
+        assert_eq!(viewshed.count(), 13);
+
+        // Standing on the wall, everything is in view.
+        assert_eq!(grid.viewshed((1, 2), 0, 10).unwrap().count(), 15);
+        assert_eq!(grid.viewshed((1, 0), 0, 1).unwrap().count(), 4);
+    }
+
+    #[test]
+    fn example() {
+        let grid = EXAMPLE.parse::<Grid>().unwrap();
+        assert_eq!(grid.viewshed((2, 2), 100, 10).unwrap().count(), 25);
+        assert_eq!(grid.viewshed((2, 2), 100, 1).unwrap().count(), 5);
+        assert!(grid.viewshed((5, 0), 0, 1).is_err());
+    }
+}
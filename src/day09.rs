@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+pub mod trail;
+
+/// A `(row, col)` position, rows counting up.
+pub type Coord = (i32, i32);
+
+/// How a knot moves after the knot ahead of it has.
+pub trait Follow {
+    /// Where a knot at `knot` ends up when the one ahead is at `leader`.
+    fn follow(&self, leader: Coord, knot: Coord) -> Coord;
+}
+
+/// Knots stay touching, diagonals included, catching up with a step in both
+/// directions at once when they can. The puzzle's rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chebyshev;
+
+/// Knots stay touching side by side, catching up one row or column at a time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Manhattan;
+
+/// Knots stay within this many rows and columns, catching up like
+/// `Chebyshev` when they fall further behind. `Slack(1)` is `Chebyshev`, and
+/// slack beyond `i32::MAX` is the same as `i32::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slack(pub u32);
+
+impl Follow for Chebyshev {
+    fn follow(&self, leader: Coord, knot: Coord) -> Coord {
+        Slack(1).follow(leader, knot)
+    }
+}
+
+impl Follow for Manhattan {
+    fn follow(&self, leader: Coord, mut knot: Coord) -> Coord {
+        loop {
+            let (drow, dcol) = (leader.0 - knot.0, leader.1 - knot.1);
+            if drow.abs() + dcol.abs() <= 1 {
+                return knot;
+            }
+            if drow.abs() >= dcol.abs() {
+                knot.0 += drow.signum();
+            } else {
+                knot.1 += dcol.signum();
+            }
+        }
+    }
+}
+
+impl Follow for Slack {
+    fn follow(&self, leader: Coord, mut knot: Coord) -> Coord {
+        let slack = i32::try_from(self.0).unwrap_or(i32::MAX);
+        while (leader.0 - knot.0).abs() > slack || (leader.1 - knot.1).abs() > slack {
+            knot.0 += (leader.0 - knot.0).signum();
+            knot.1 += (leader.1 - knot.1).signum();
+        }
+        knot
+    }
+}
+
+/// One of the follow rules, picked by name: `chebyshev`, `manhattan` or
+/// `slack:N`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Chebyshev,
+    Manhattan,
+    Slack(u32),
+}
+
+impl Follow for Rule {
+    fn follow(&self, leader: Coord, knot: Coord) -> Coord {
+        match self {
+            Rule::Chebyshev => Chebyshev.follow(leader, knot),
+            Rule::Manhattan => Manhattan.follow(leader, knot),
+            Rule::Slack(slack) => Slack(*slack).follow(leader, knot),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "chebyshev" => Ok(Rule::Chebyshev),
+            None if s == "manhattan" => Ok(Rule::Manhattan),
+            Some(("slack", slack)) => match slack.parse::<u32>()? {
+                slack if slack > i32::MAX as u32 => {
+                    Err(anyhow!("Slack can be at most {}, not {}", i32::MAX, slack))
+                }
+                slack => Ok(Rule::Slack(slack)),
+            },
+            _ => Err(anyhow!("Unrecognized follow rule: '{}'", s)),
+        }
+    }
+}
+
+/// A rope of any number of knots, the first being the head.
+#[derive(Debug, Clone)]
+pub struct Rope<R: Follow> {
+    knots: Vec<Coord>,
+    rule: R,
+}
+
+impl<R: Follow> Rope<R> {
+    pub fn new(len: usize, rule: R) -> Result<Self> {
+        if len == 0 {
+            Err(anyhow!("A rope needs at least one knot"))?;
+        }
+        Ok(Rope {
+            knots: vec![(0, 0); len],
+            rule,
+        })
+    }
+
+    pub fn knots(&self) -> &[Coord] {
+        &self.knots
+    }
+
+    pub fn head(&self) -> Coord {
+        self.knots[0]
+    }
+
+    pub fn tail(&self) -> Coord {
+        self.knots[self.knots.len() - 1]
+    }
+
+    /// Moves the head one step and every other knot after it.
+    pub fn wiggle(&mut self, dir: Direction) {
+        let (drow, dcol) = dir.step();
+        self.knots[0].0 += drow;
+        self.knots[0].1 += dcol;
+        for i in 1..self.knots.len() {
+            self.knots[i] = self.rule.follow(self.knots[i - 1], self.knots[i]);
+        }
+    }
+}
+
+/// How many positions the tail of a rope of `len` knots visits.
+pub fn tail_visits(motions: &[Motion], len: usize, rule: impl Follow) -> Result<usize> {
+    let mut rope = Rope::new(len, rule)?;
+    let mut visited = HashSet::from([rope.tail()]);
+    for motion in motions {
+        for _ in 0..motion.count {
+            rope.wiggle(motion.dir);
+            visited.insert(rope.tail());
+        }
+    }
+    Ok(visited.len())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Motion {
+    pub dir: Direction,
+    pub count: usize,
+}
+
+impl FromStr for Motion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (dir, count) = s
+            .split_once(' ')
+            .ok_or(anyhow!("Missing delimeter in line!"))?;
+        Ok(Motion {
+            dir: dir.parse()?,
+            count: count.parse()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    pub fn step(&self) -> Coord {
+        match self {
+            Direction::Up => (1, 0),
+            Direction::Down => (-1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::UpLeft => (1, -1),
+            Direction::UpRight => (1, 1),
+            Direction::DownLeft => (-1, -1),
+            Direction::DownRight => (-1, 1),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "U" => Ok(Direction::Up),
+            "D" => Ok(Direction::Down),
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            "UL" => Ok(Direction::UpLeft),
+            "UR" => Ok(Direction::UpRight),
+            "DL" => Ok(Direction::DownLeft),
+            "DR" => Ok(Direction::DownRight),
+            _ => Err(anyhow!("Unrecognized direction: '{}'", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) const EXAMPLE: &str = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2
+";
+
+#[cfg(test)]
+pub(crate) const LARGER_EXAMPLE: &str = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20
+";
+
+#[cfg(test)]
+pub(crate) fn motions(s: &str) -> Vec<Motion> {
+    s.lines().map(|line| line.parse().unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn examples() {
+        assert_eq!(tail_visits(&motions(EXAMPLE), 2, Chebyshev).unwrap(), 13);
+        assert_eq!(tail_visits(&motions(EXAMPLE), 10, Chebyshev).unwrap(), 1);
+        assert_eq!(
+            tail_visits(&motions(LARGER_EXAMPLE), 10, Chebyshev).unwrap(),
+            36
+        );
+        assert_eq!(
+            tail_visits(&motions(LARGER_EXAMPLE), 10, Slack(1)).unwrap(),
+            36
+        );
+        assert!(Rope::new(0, Chebyshev).is_err());
+    }
+
+    #[test]
+    fn diagonal_motions() {
+        let mut rope = Rope::new(3, Chebyshev).unwrap();
+        for _ in 0..3 {
+            rope.wiggle(Direction::UpRight);
+        }
+        assert_eq!(rope.knots(), [(3, 3), (2, 2), (1, 1)]);
+        rope.wiggle(Direction::DownRight);
+        assert_eq!(rope.knots(), [(2, 4), (2, 3), (2, 2)]);
+        rope.wiggle(Direction::Right);
+        assert_eq!(rope.knots(), [(2, 5), (2, 4), (2, 3)]);
+    }
+
+    #[test]
+    fn rules() {
+        assert_eq!(Chebyshev.follow((1, 1), (0, 0)), (0, 0));
+        assert_eq!(Chebyshev.follow((2, 1), (0, 0)), (1, 1));
+        assert_eq!(Manhattan.follow((1, 1), (0, 0)), (1, 0));
+        assert_eq!(Manhattan.follow((1, 3), (0, 0)), (1, 2));
+        assert_eq!(Slack(2).follow((2, -2), (0, 0)), (0, 0));
+        assert_eq!(Slack(2).follow((5, -1), (0, 0)), (3, -1));
+        assert_eq!(Slack(u32::MAX).follow((5, -1), (0, 0)), (0, 0));
+
+        let mut rope = Rope::new(3, Manhattan).unwrap();
+        rope.wiggle(Direction::UpRight);
+        assert_eq!(rope.knots(), [(1, 1), (1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("slack:3".parse::<Rule>().unwrap(), Rule::Slack(3));
+        assert_eq!("manhattan".parse::<Rule>().unwrap(), Rule::Manhattan);
+        assert!("slack".parse::<Rule>().is_err());
+        assert!("slack:2147483648".parse::<Rule>().is_err());
+        assert_eq!(
+            "DL 3".parse::<Motion>().unwrap(),
+            Motion {
+                dir: Direction::DownLeft,
+                count: 3
+            }
+        );
+        assert!("X 3".parse::<Motion>().is_err());
+    }
+}
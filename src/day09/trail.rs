@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+
+use anyhow::{anyhow, Result};
+
+use super::{Coord, Follow, Motion, Rope};
+
+/// The smallest rectangle around some positions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: Coord,
+    pub max: Coord,
+}
+
+impl Bounds {
+    pub fn around(coords: impl IntoIterator<Item = Coord>) -> Option<Self> {
+        coords.into_iter().fold(None, |bounds, coord| {
+            Some(match bounds {
+                None => Bounds {
+                    min: coord,
+                    max: coord,
+                },
+                Some(Bounds { min, max }) => Bounds {
+                    min: (min.0.min(coord.0), min.1.min(coord.1)),
+                    max: (max.0.max(coord.0), max.1.max(coord.1)),
+                },
+            })
+        })
+    }
+
+    /// The number of rows and columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (
+            (self.max.0 - self.min.0 + 1) as usize,
+            (self.max.1 - self.min.1 + 1) as usize,
+        )
+    }
+}
+
+/// Where every knot of a rope was after every step, starting with where they
+/// all started.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trails {
+    frames: Vec<Vec<Coord>>,
+    /// The frame each motion ended on.
+    motion_ends: Vec<usize>,
+}
+
+impl Trails {
+    pub fn record<R: Follow>(mut rope: Rope<R>, motions: &[Motion]) -> Self {
+        let mut frames = vec![rope.knots().to_vec()];
+        let mut motion_ends = Vec::new();
+        for motion in motions {
+            for _ in 0..motion.count {
+                rope.wiggle(motion.dir);
+                frames.push(rope.knots().to_vec());
+            }
+            motion_ends.push(frames.len() - 1);
+        }
+        Trails {
+            frames,
+            motion_ends,
+        }
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn knots(&self) -> usize {
+        self.frames[0].len()
+    }
+
+    pub fn motion_ends(&self) -> &[usize] {
+        &self.motion_ends
+    }
+
+    pub fn frame(&self, frame: usize) -> Result<&[Coord]> {
+        self.frames
+            .get(frame)
+            .map(|knots| knots.as_slice())
+            .ok_or(anyhow!(
+                "There is no frame {}, only {}",
+                frame,
+                self.frames.len()
+            ))
+    }
+
+    /// Where `knot` was in every frame.
+    pub fn trail(&self, knot: usize) -> impl Iterator<Item = Coord> + '_ {
+        self.frames.iter().map(move |knots| knots[knot])
+    }
+
+    /// How many times `knot` arrived at each position it was at, starting
+    /// there counting as arriving and staying put not counting.
+    pub fn visits(&self, knot: usize) -> HashMap<Coord, usize> {
+        let mut visits = HashMap::new();
+        let mut last = None;
+        for coord in self.trail(knot) {
+            if last != Some(coord) {
+                *visits.entry(coord).or_default() += 1;
+            }
+            last = Some(coord);
+        }
+        visits
+    }
+
+    pub fn stats(&self, knot: usize) -> KnotStats {
+        let visits = self.visits(knot);
+        let mut revisits = BTreeMap::new();
+        for &count in visits.values() {
+            *revisits.entry(count).or_default() += 1;
+        }
+        KnotStats {
+            knot,
+            visited: visits.len(),
+            arrivals: visits.values().sum(),
+            revisits,
+            bounds: Bounds::around(self.trail(knot)).unwrap(),
+        }
+    }
+
+    /// The bounds of every knot over every frame, so a replay drawn in them
+    /// never has to move.
+    pub fn bounds(&self) -> Bounds {
+        Bounds::around(self.frames.iter().flatten().copied()).unwrap()
+    }
+
+    /// Draws `frame` as the puzzle does, the head as `H`, the others by their
+    /// number, or `T` if there is just the one, and the start as `s`. Knots
+    /// nearer the head cover those behind them. Without `bounds`, the frame
+    /// is drawn just big enough for its knots and the start.
+    pub fn render(&self, frame: usize, bounds: Option<Bounds>) -> Result<String> {
+        let knots = self.frame(frame)?;
+        let bounds = bounds
+            .unwrap_or_else(|| Bounds::around(knots.iter().copied().chain([(0, 0)])).unwrap());
+        let (nrows, ncols) = bounds.shape();
+
+        let mut screen = vec![vec!['.'; ncols]; nrows];
+        let mut draw = |(row, col): Coord, label: char| {
+            if (bounds.min.0..=bounds.max.0).contains(&row)
+                && (bounds.min.1..=bounds.max.1).contains(&col)
+            {
+                let row = (bounds.max.0 - row) as usize;
+                screen[row][(col - bounds.min.1) as usize] = label;
+            }
+        };
+
+        draw((0, 0), 's');
+        for (idx, &knot) in knots.iter().enumerate().rev() {
+            let label = match idx {
+                0 => 'H',
+                1 if knots.len() == 2 => 'T',
+                idx => char::from_digit((idx % 10) as u32, 10).unwrap(),
+            };
+            draw(knot, label);
+        }
+
+        Ok(screen
+            .into_iter()
+            .map(|row| row.into_iter().collect::<String>() + "\n")
+            .collect())
+    }
+}
+
+/// How one knot got around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnotStats {
+    pub knot: usize,
+    /// Distinct positions.
+    pub visited: usize,
+    /// Times it arrived anywhere.
+    pub arrivals: usize,
+    /// How many positions were arrived at once, twice and so on.
+    pub revisits: BTreeMap<usize, usize>,
+    pub bounds: Bounds,
+}
+
+impl Display for KnotStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let revisits = self
+            .revisits
+            .iter()
+            .map(|(times, positions)| format!("{}x{}", positions, times))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(
+            f,
+            "knot {}: {} positions, {} arrivals, rows {}..={}, cols {}..={}, visits {}",
+            self.knot,
+            self.visited,
+            self.arrivals,
+            self.bounds.min.0,
+            self.bounds.max.0,
+            self.bounds.min.1,
+            self.bounds.max.1,
+            revisits
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{motions, Chebyshev, EXAMPLE, LARGER_EXAMPLE};
+    use super::*;
+
+    #[test]
+    fn example() {
+        let trails = Trails::record(Rope::new(2, Chebyshev).unwrap(), &motions(EXAMPLE));
+        assert_eq!(trails.frames(), 25);
+        assert_eq!(trails.motion_ends().len(), 8);
+        assert_eq!(trails.stats(1).visited, 13);
+        assert_eq!(
+            trails.stats(0).bounds,
+            Bounds {
+                min: (0, 0),
+                max: (4, 5)
+            }
+        );
+        assert_eq!(trails.render(0, None).unwrap(), "H\n");
+        assert_eq!(trails.render(4, None).unwrap(), "s..TH\n");
+        assert_eq!(
+            trails.render(24, Some(trails.bounds())).unwrap(),
+            "......\n......\n.TH...\n......\ns.....\n"
+        );
+        assert!(trails.render(25, None).is_err());
+    }
+
+    #[test]
+    fn larger_example() {
+        let rope = Rope::new(10, Chebyshev).unwrap();
+        let trails = Trails::record(rope, &motions(LARGER_EXAMPLE));
+        assert_eq!(trails.stats(9).visited, 36);
+
+        let end = trails.motion_ends()[1];
+        assert_eq!(
+            trails.render(end, None).unwrap(),
+            ".....H\n\
+             .....1\n\
+             .....2\n\
+             .....3\n\
+             ....54\n\
+             ...6..\n\
+             ..7...\n\
+             .8....\n\
+             9.....\n"
+        );
+    }
+
+    #[test]
+    fn revisits() {
+        let motions = ["R 2", "L 2", "R 1"].map(|line| line.parse().unwrap());
+        let trails = Trails::record(Rope::new(1, Chebyshev).unwrap(), &motions);
+        let stats = trails.stats(0);
+        assert_eq!(stats.visited, 3);
+        assert_eq!(stats.arrivals, 6);
+        assert_eq!(stats.revisits, BTreeMap::from([(1, 1), (2, 1), (3, 1)]));
+        assert_eq!(
+            stats.to_string(),
+            "knot 0: 3 positions, 6 arrivals, rows 0..=0, cols 0..=2, visits 1x1 1x2 1x3"
+        );
+    }
+}
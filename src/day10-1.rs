@@ -1,108 +1,102 @@
-use std::str::FromStr;
-
 use anyhow::anyhow;
-use anyhow::Error;
 use anyhow::Result;
 
 use aoc::collect_lines;
+use aoc::day10::asm::assemble;
+use aoc::day10::query::{write_csv, Signal};
+use aoc::day10::vm::Vm;
+use aoc::day10::Instruction;
+use aoc::input_file;
 use aoc::time_it;
 
+const FIRST_CYCLE: usize = 20;
+const CYCLE_STEP: usize = 40;
+/// Programs with jumps may never end, so no more cycles than this are run.
+const MAX_CYCLES: usize = 1_000_000;
+
+/// Pass `--trace` to print the machine during every cycle, or `--run FILE`
+/// to assemble and trace another program.
+///
+/// `--x CYCLE...` prints `X` during the given cycles and `--strength CYCLE...`
+/// the total signal strength over them, while `--when VALUE` lists the cycles
+/// `X` has that value and `--csv` prints the register trace as CSV. Only as
+/// many cycles as asked about are run, and at most `MAX_CYCLES` otherwise.
 fn main() -> Result<()> {
-    time_it(|| solution())?;
-    Ok(())
-}
-
-fn solution() -> Result<i32> {
-    let instructions = collect_lines::<Instruction>("input/day10.txt")?;
-
-    let cpu_iter = CpuIterator::new(&instructions);
-    Ok(cpu_iter
-        .enumerate()
-        .filter_map(|(cycle, strength)| is_special(cycle + 2).then_some(strength))
-        .sum())
-}
-
-struct CpuIterator<'a> {
-    cycle: i32,
-    x: i32,
-    addx: Option<i32>,
-    instructions: std::slice::Iter<'a, Instruction>,
-}
-
-impl<'a> CpuIterator<'a> {
-    fn new(instructions: &'a [Instruction]) -> Self {
-        CpuIterator {
-            cycle: 1,
-            x: 1,
-            addx: None,
-            instructions: instructions.iter(),
+    let args = std::env::args().collect::<Vec<_>>();
+    let cycles = || {
+        args[2..]
+            .iter()
+            .map(|arg| arg.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+    };
+    match args.get(1).map(|arg| arg.as_str()) {
+        Some("--x") => {
+            let cycles = cycles()?;
+            let signal = signal(cycles.iter().copied().max().unwrap_or(0))?;
+            for cycle in cycles {
+                println!("{} {}", cycle, signal.x(cycle)?);
+            }
         }
-    }
-}
-
-impl Iterator for CpuIterator<'_> {
-    type Item = i32;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.cycle += 1;
-        if let Some(addx) = self.addx {
-            self.x += addx;
-            self.addx = None;
-        } else if let Some(instruction) = self.instructions.next() {
-            if let Instruction::Addx(addx) = instruction {
-                self.addx = Some(*addx);
+        Some("--strength") => {
+            let cycles = cycles()?;
+            let signal = signal(cycles.iter().copied().max().unwrap_or(0))?;
+            println!("{}", signal.total_strength(cycles)?);
+        }
+        Some("--when") => {
+            let value = args.get(2).ok_or(anyhow!("--when needs a value"))?;
+            let signal = signal(MAX_CYCLES)?;
+            for range in signal.cycles_where(value.parse()?) {
+                println!("{}..={}", range.start(), range.end());
             }
-        } else {
-            return None;
+            if !signal.finished() {
+                eprintln!("Stopped after {} cycles", signal.cycles());
+            }
+        }
+        Some("--csv") => write_csv(
+            &collect_lines::<Instruction>(input_file(10)?)?,
+            MAX_CYCLES,
+            std::io::stdout().lock(),
+        )?,
+        Some("--trace") => trace(&collect_lines::<Instruction>(input_file(10)?)?)?,
+        Some("--run") => {
+            let fname = args.get(2).ok_or(anyhow!("--run needs a program"))?;
+            trace(&assemble(&std::fs::read_to_string(fname)?)?)?;
         }
-        Some(self.cycle * self.x)
+        _ => time_it(|| solution())?,
     }
+    Ok(())
 }
 
-fn is_special(cycle: usize) -> bool {
-    match cycle {
-        20 | 60 | 100 | 140 | 180 | 220 => true,
-        _ => false,
+fn trace(program: &[Instruction]) -> Result<()> {
+    let mut trace = Vm::new(program).trace();
+    for step in trace.by_ref().take(MAX_CYCLES) {
+        println!("{}", step);
+    }
+    if trace.next().is_some() {
+        Err(anyhow!(
+            "The program is still running after {} cycles",
+            MAX_CYCLES
+        ))?;
     }
+    Ok(())
 }
 
-#[derive(Debug, PartialEq)]
-enum Instruction {
-    Noop,
-    Addx(i32),
+fn signal(max_cycles: usize) -> Result<Signal> {
+    Ok(Signal::new(
+        &collect_lines::<Instruction>(input_file(10)?)?,
+        max_cycles,
+    ))
 }
 
-impl FromStr for Instruction {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut itr = s.split_whitespace();
-        match itr.next() {
-            Some("noop") => Ok(Self::Noop),
-            Some("addx") => Ok(Self::Addx(
-                itr.next()
-                    .ok_or(anyhow!("addx instruction missing amount!"))?
-                    .parse()?,
-            )),
-            _ => Err(anyhow!("Invalid instruction: {}", s)),
-        }
-    }
+fn solution() -> Result<i32> {
+    let signal = signal(MAX_CYCLES)?;
+    signal.total_strength(signal.every(FIRST_CYCLE, CYCLE_STEP))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use rstest::rstest;
-
-    #[rstest]
-    #[case("noop", Instruction::Noop)]
-    #[case("addx 3", Instruction::Addx(3))]
-    #[case("addx -20", Instruction::Addx(-20))]
-    fn instruction_from_str(#[case] input: &str, #[case] expected: Instruction) {
-        assert_eq!(input.parse::<Instruction>().unwrap(), expected);
-    }
-
     #[test]
     fn sln() {
         assert_eq!(solution().unwrap(), 15120);
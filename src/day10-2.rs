@@ -1,10 +1,9 @@
-use std::str::FromStr;
-
-use anyhow::anyhow;
-use anyhow::Error;
 use anyhow::Result;
 
 use aoc::collect_lines;
+use aoc::day10::CpuIterator;
+use aoc::day10::Instruction;
+use aoc::input_file;
 use aoc::time_it;
 
 const WIDTH: usize = 40;
@@ -16,7 +15,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<String> {
-    let instructions = collect_lines::<Instruction>("input/day10.txt")?;
+    let instructions = collect_lines::<Instruction>(input_file(10)?)?;
     let mut cpu_iter = CpuIterator::new(&instructions);
 
     Ok((0..HEIGHT)
@@ -38,82 +37,10 @@ fn draw(col: usize, x: i32) -> bool {
     }
 }
 
-struct CpuIterator<'a> {
-    cycle: i32,
-    x: i32,
-    addx: Option<i32>,
-    instructions: std::slice::Iter<'a, Instruction>,
-}
-
-impl<'a> CpuIterator<'a> {
-    fn new(instructions: &'a [Instruction]) -> Self {
-        CpuIterator {
-            cycle: 0,
-            x: 1,
-            addx: None,
-            instructions: instructions.iter(),
-        }
-    }
-}
-
-impl Iterator for CpuIterator<'_> {
-    type Item = i32;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.cycle += 1;
-        if self.cycle == 1 {
-            {}
-        } else if let Some(addx) = self.addx {
-            self.x += addx;
-            self.addx = None;
-        } else if let Some(instruction) = self.instructions.next() {
-            if let Instruction::Addx(addx) = instruction {
-                self.addx = Some(*addx);
-            }
-        } else {
-            return None;
-        }
-        Some(self.x)
-    }
-}
-
-#[derive(Debug, PartialEq)]
-enum Instruction {
-    Noop,
-    Addx(i32),
-}
-
-impl FromStr for Instruction {
-    type Err = Error;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut itr = s.split_whitespace();
-        match itr.next() {
-            Some("noop") => Ok(Self::Noop),
-            Some("addx") => Ok(Self::Addx(
-                itr.next()
-                    .ok_or(anyhow!("addx instruction missing amount!"))?
-                    .parse()?,
-            )),
-            _ => Err(anyhow!("Invalid instruction: {}", s)),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use rstest::rstest;
-
-    #[rstest]
-    #[case("noop", Instruction::Noop)]
-    #[case("addx 3", Instruction::Addx(3))]
-    #[case("addx -20", Instruction::Addx(-20))]
-    fn instruction_from_str(#[case] input: &str, #[case] expected: Instruction) {
-        assert_eq!(input.parse::<Instruction>().unwrap(), expected);
-    }
-
     #[test]
     fn sln() {
         assert_eq!(
@@ -0,0 +1,277 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Error, Result};
+
+pub mod asm;
+pub mod query;
+pub mod vm;
+
+use vm::{Trace, Vm};
+
+/// One of the registers `a` to `z`, `x` being the puzzle's `X`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Register(u8);
+
+impl Register {
+    pub const COUNT: usize = 26;
+    pub const X: Register = Register(b'x' - b'a');
+
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl FromStr for Register {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.as_bytes() {
+            [c @ b'a'..=b'z'] => Ok(Register(c - b'a')),
+            _ => Err(anyhow!("Invalid register: {}", s)),
+        }
+    }
+}
+
+impl Display for Register {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", (b'a' + self.0) as char)
+    }
+}
+
+/// A register or a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Register(Register),
+    Value(i32),
+}
+
+impl FromStr for Operand {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse() {
+            Ok(value) => Ok(Operand::Value(value)),
+            Err(_) => Ok(Operand::Register(s.parse()?)),
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Jumps go to the index of an instruction in the program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Noop,
+    /// Adds to `x`.
+    Addx(i32),
+    Add(Register, Operand),
+    Set(Register, Operand),
+    Jmp(usize),
+    /// Jumps if the register is zero.
+    Jz(Register, usize),
+    /// Jumps if the register isn't zero.
+    Jnz(Register, usize),
+    Halt,
+}
+
+/// What the machine knows about an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opcode {
+    pub name: &'static str,
+    pub operands: usize,
+    pub cycles: usize,
+}
+
+/// Every instruction, in the order of `Instruction`'s variants.
+pub const OPCODES: [Opcode; 8] = [
+    Opcode {
+        name: "noop",
+        operands: 0,
+        cycles: 1,
+    },
+    Opcode {
+        name: "addx",
+        operands: 1,
+        cycles: 2,
+    },
+    Opcode {
+        name: "add",
+        operands: 2,
+        cycles: 2,
+    },
+    Opcode {
+        name: "set",
+        operands: 2,
+        cycles: 1,
+    },
+    Opcode {
+        name: "jmp",
+        operands: 1,
+        cycles: 1,
+    },
+    Opcode {
+        name: "jz",
+        operands: 2,
+        cycles: 1,
+    },
+    Opcode {
+        name: "jnz",
+        operands: 2,
+        cycles: 1,
+    },
+    Opcode {
+        name: "halt",
+        operands: 0,
+        cycles: 1,
+    },
+];
+
+impl Instruction {
+    pub fn opcode(&self) -> &'static Opcode {
+        let idx = match self {
+            Instruction::Noop => 0,
+            Instruction::Addx(_) => 1,
+            Instruction::Add(..) => 2,
+            Instruction::Set(..) => 3,
+            Instruction::Jmp(_) => 4,
+            Instruction::Jz(..) => 5,
+            Instruction::Jnz(..) => 6,
+            Instruction::Halt => 7,
+        };
+        &OPCODES[idx]
+    }
+
+    pub fn cycles(&self) -> usize {
+        self.opcode().cycles
+    }
+
+    /// Builds an instruction from its name and operands, with `target`
+    /// turning a jump's operand into the index it goes to.
+    pub(crate) fn build(
+        name: &str,
+        operands: &[&str],
+        target: impl Fn(&str) -> Result<usize>,
+    ) -> Result<Self> {
+        let opcode = OPCODES
+            .iter()
+            .find(|opcode| opcode.name == name)
+            .ok_or(anyhow!("Invalid instruction: {}", name))?;
+        if operands.len() != opcode.operands {
+            Err(anyhow!(
+                "{} takes {} operands, not {}",
+                name,
+                opcode.operands,
+                operands.len()
+            ))?;
+        }
+
+        Ok(match (name, operands) {
+            ("noop", []) => Instruction::Noop,
+            ("addx", [value]) => Instruction::Addx(value.parse()?),
+            ("add", [register, operand]) => Instruction::Add(register.parse()?, operand.parse()?),
+            ("set", [register, operand]) => Instruction::Set(register.parse()?, operand.parse()?),
+            ("jmp", [to]) => Instruction::Jmp(target(to)?),
+            ("jz", [register, to]) => Instruction::Jz(register.parse()?, target(to)?),
+            ("jnz", [register, to]) => Instruction::Jnz(register.parse()?, target(to)?),
+            _ => Instruction::Halt,
+        })
+    }
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    /// Parses an instruction on its own, so jumps need the index they go to.
+    /// See `asm::assemble` for whole programs with labels.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut itr = s.split_whitespace();
+        let name = itr.next().ok_or(anyhow!("Invalid instruction: {}", s))?;
+        let operands = itr.collect::<Vec<_>>();
+        Instruction::build(name, &operands, |to| Ok(to.parse()?))
+    }
+}
+
+impl Display for Instruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = self.opcode().name;
+        match self {
+            Instruction::Noop | Instruction::Halt => write!(f, "{}", name),
+            Instruction::Addx(value) => write!(f, "{} {}", name, value),
+            Instruction::Add(register, operand) | Instruction::Set(register, operand) => {
+                write!(f, "{} {} {}", name, register, operand)
+            }
+            Instruction::Jmp(to) => write!(f, "{} {}", name, to),
+            Instruction::Jz(register, to) | Instruction::Jnz(register, to) => {
+                write!(f, "{} {} {}", name, register, to)
+            }
+        }
+    }
+}
+
+/// The value of `x` during every cycle of a program, from the first.
+pub struct CpuIterator<'a> {
+    trace: Trace<'a>,
+}
+
+impl<'a> CpuIterator<'a> {
+    pub fn new(instructions: &'a [Instruction]) -> Self {
+        CpuIterator {
+            trace: Vm::new(instructions).trace(),
+        }
+    }
+}
+
+impl Iterator for CpuIterator<'_> {
+    type Item = i32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.trace.next().map(|step| step.get(Register::X))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("noop", Instruction::Noop)]
+    #[case("addx 3", Instruction::Addx(3))]
+    #[case("addx -20", Instruction::Addx(-20))]
+    #[case("add a x", Instruction::Add("a".parse().unwrap(), Operand::Register(Register::X)))]
+    #[case("set b -2", Instruction::Set("b".parse().unwrap(), Operand::Value(-2)))]
+    #[case("jnz x 3", Instruction::Jnz(Register::X, 3))]
+    #[case("halt", Instruction::Halt)]
+    fn instruction_from_str(#[case] input: &str, #[case] expected: Instruction) {
+        assert_eq!(input.parse::<Instruction>().unwrap(), expected);
+        assert_eq!(expected.to_string(), input);
+    }
+
+    #[rstest]
+    #[case("addx", "addx takes 1 operands, not 0")]
+    #[case("mul x 2", "Invalid instruction: mul")]
+    #[case("add X 2", "Invalid register: X")]
+    #[case("", "Invalid instruction: ")]
+    fn invalid_instructions(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(
+            input.parse::<Instruction>().unwrap_err().to_string(),
+            expected
+        );
+    }
+
+    #[test]
+    fn cpu_iterator() {
+        let instructions = ["noop", "addx 3", "addx -5"].map(|line| line.parse().unwrap());
+        let xs = CpuIterator::new(&instructions).collect::<Vec<_>>();
+        assert_eq!(xs, vec![1, 1, 1, 4, 4]);
+    }
+}
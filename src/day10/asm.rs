@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+
+use super::Instruction;
+
+/// Assembles a program, one instruction a line.
+///
+/// - `name:` labels the instruction after it, on the same line or the next.
+/// - Jumps go to a label, `+N` or `-N` instructions on from the jump, or the
+///   instruction with index `N`.
+/// - `#` starts a comment, and blank lines are skipped.
+pub fn assemble(source: &str) -> Result<Vec<Instruction>> {
+    let mut labels = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (idx, line) in source.lines().enumerate() {
+        let mut line = line.split('#').next().unwrap().trim();
+        if let Some((label, rest)) = line.split_once(':') {
+            let label = label.trim();
+            if label.is_empty() || label.contains(char::is_whitespace) {
+                Err(anyhow!("Line {}: invalid label '{}'", idx + 1, label))?;
+            }
+            if labels.insert(label, lines.len()).is_some() {
+                Err(anyhow!("Line {}: {} is already a label", idx + 1, label))?;
+            }
+            line = rest.trim();
+        }
+        if !line.is_empty() {
+            lines.push((idx + 1, line));
+        }
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(pc, &(line, text))| {
+            let target = |to: &str| match to.as_bytes().first() {
+                Some(b'+' | b'-') => pc
+                    .checked_add_signed(to.parse()?)
+                    .ok_or(anyhow!("{} jumps to before the program", to)),
+                Some(b'0'..=b'9') => Ok(to.parse()?),
+                _ => labels
+                    .get(to)
+                    .copied()
+                    .ok_or(anyhow!("there is no label {}", to)),
+            };
+            let mut itr = text.split_whitespace();
+            let name = itr.next().unwrap();
+            let operands = itr.collect::<Vec<_>>();
+            Instruction::build(name, &operands, target).map_err(|e| anyhow!("Line {}: {}", line, e))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Operand, Register};
+    use super::*;
+
+    #[test]
+    fn labels() {
+        let program = assemble(
+            "start: set a 2  # two times round\n\
+             \n\
+             loop:\n\
+             add a -1\n\
+             jnz a loop\n\
+             jz a +2\n\
+             jmp start\n\
+             end: halt",
+        )
+        .unwrap();
+        let a = "a".parse::<Register>().unwrap();
+        assert_eq!(
+            program,
+            vec![
+                Instruction::Set(a, Operand::Value(2)),
+                Instruction::Add(a, Operand::Value(-1)),
+                Instruction::Jnz(a, 1),
+                Instruction::Jz(a, 5),
+                Instruction::Jmp(0),
+                Instruction::Halt,
+            ]
+        );
+    }
+
+    #[test]
+    fn puzzle_programs() {
+        assert_eq!(
+            assemble("noop\naddx 3\naddx -5").unwrap(),
+            vec![
+                Instruction::Noop,
+                Instruction::Addx(3),
+                Instruction::Addx(-5)
+            ]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |source| assemble(source).unwrap_err().to_string();
+        assert_eq!(
+            error("noop\njmp nowhere"),
+            "Line 2: there is no label nowhere"
+        );
+        assert_eq!(error("a:\na: noop"), "Line 2: a is already a label");
+        assert_eq!(error("jmp -1"), "Line 1: -1 jumps to before the program");
+        assert_eq!(error("\n\nadd x"), "Line 3: add takes 2 operands, not 1");
+    }
+}
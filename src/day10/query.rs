@@ -0,0 +1,252 @@
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::ops::RangeInclusive;
+
+use anyhow::{anyhow, Result};
+
+use super::vm::Vm;
+use super::{CpuIterator, Instruction, Operand, Register};
+
+/// The value of `x` during every cycle of a program, for asking about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signal {
+    xs: Vec<i32>,
+    /// Whether the program ended within the cycles run.
+    finished: bool,
+}
+
+impl Signal {
+    /// Runs the program for at most `max_cycles`, as with jumps it may never
+    /// end.
+    pub fn new(instructions: &[Instruction], max_cycles: usize) -> Self {
+        let mut cpu = CpuIterator::new(instructions);
+        let xs = cpu.by_ref().take(max_cycles).collect();
+        Signal {
+            xs,
+            finished: cpu.next().is_none(),
+        }
+    }
+
+    /// The number of cycles the program ran for, or was run for if it didn't
+    /// end.
+    pub fn cycles(&self) -> usize {
+        self.xs.len()
+    }
+
+    pub fn finished(&self) -> bool {
+        self.finished
+    }
+
+    /// `x` during `cycle`, counting from 1.
+    pub fn x(&self, cycle: usize) -> Result<i32> {
+        cycle
+            .checked_sub(1)
+            .and_then(|idx| self.xs.get(idx))
+            .copied()
+            .ok_or_else(|| match self.finished {
+                true => anyhow!(
+                    "Cycle {} is outside the program's {} cycles",
+                    cycle,
+                    self.xs.len()
+                ),
+                false => anyhow!(
+                    "Cycle {} is outside the {} cycles the program was run for",
+                    cycle,
+                    self.xs.len()
+                ),
+            })
+    }
+
+    pub fn xs(&self, cycles: impl IntoIterator<Item = usize>) -> Result<Vec<i32>> {
+        cycles.into_iter().map(|cycle| self.x(cycle)).collect()
+    }
+
+    /// The cycle number times `x` during it.
+    pub fn strength(&self, cycle: usize) -> Result<i32> {
+        let x = self.x(cycle)?;
+        i32::try_from(cycle)
+            .ok()
+            .and_then(|cycle| cycle.checked_mul(x))
+            .ok_or(anyhow!(
+                "The signal strength during cycle {} overflows, x being {}",
+                cycle,
+                x
+            ))
+    }
+
+    pub fn total_strength(&self, cycles: impl IntoIterator<Item = usize>) -> Result<i32> {
+        cycles.into_iter().try_fold(0i32, |total, cycle| {
+            total.checked_add(self.strength(cycle)?).ok_or(anyhow!(
+                "The total signal strength overflows at cycle {}",
+                cycle
+            ))
+        })
+    }
+
+    /// Cycle `first` and every `step` cycles after it the program ran for.
+    pub fn every(&self, first: usize, step: usize) -> impl Iterator<Item = usize> {
+        (first..=self.xs.len()).step_by(step.max(1))
+    }
+
+    /// The runs of cycles during which `x` was `value`.
+    pub fn cycles_where(&self, value: i32) -> Vec<RangeInclusive<usize>> {
+        let mut ranges: Vec<RangeInclusive<usize>> = Vec::new();
+        for (cycle, &x) in (1..).zip(&self.xs) {
+            if x != value {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(range) if *range.end() + 1 == cycle => *range = *range.start()..=cycle,
+                _ => ranges.push(cycle..=cycle),
+            }
+        }
+        ranges
+    }
+}
+
+/// Writes what every register the program uses held during every cycle as
+/// CSV, `x` first, along with the instruction being carried out. Errors if
+/// the program is still running after `max_cycles`.
+pub fn write_csv(
+    instructions: &[Instruction],
+    max_cycles: usize,
+    mut out: impl Write,
+) -> Result<()> {
+    let mut registers = BTreeSet::new();
+    for instruction in instructions {
+        match *instruction {
+            Instruction::Add(register, operand) | Instruction::Set(register, operand) => {
+                registers.insert(register);
+                if let Operand::Register(register) = operand {
+                    registers.insert(register);
+                }
+            }
+            Instruction::Jz(register, _) | Instruction::Jnz(register, _) => {
+                registers.insert(register);
+            }
+            _ => {}
+        }
+    }
+    registers.remove(&Register::X);
+    let registers = [Register::X]
+        .into_iter()
+        .chain(registers)
+        .collect::<Vec<_>>();
+
+    let names = registers.iter().map(|register| register.to_string());
+    writeln!(
+        out,
+        "cycle,pc,instruction,{}",
+        names.collect::<Vec<_>>().join(",")
+    )?;
+    let mut trace = Vm::new(instructions).trace();
+    for step in trace.by_ref().take(max_cycles) {
+        let values = registers
+            .iter()
+            .map(|&register| step.get(register).to_string());
+        writeln!(
+            out,
+            "{},{},{},{}",
+            step.cycle,
+            step.pc,
+            step.instruction,
+            values.collect::<Vec<_>>().join(",")
+        )?;
+    }
+    if trace.next().is_some() {
+        Err(anyhow!(
+            "The program is still running after {} cycles",
+            max_cycles
+        ))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::asm::assemble;
+    use super::*;
+
+    fn signal() -> Signal {
+        Signal::new(
+            &assemble("noop\naddx 3\naddx -5\nnoop\naddx 5").unwrap(),
+            100,
+        )
+    }
+
+    #[test]
+    fn queries() {
+        let signal = signal();
+        assert_eq!(signal.cycles(), 8);
+        assert_eq!(signal.xs(1..=8).unwrap(), vec![1, 1, 1, 4, 4, -1, -1, -1]);
+        assert_eq!(signal.strength(4).unwrap(), 16);
+        assert_eq!(signal.total_strength([2, 4, 6]).unwrap(), 2 + 16 - 6);
+        assert_eq!(signal.every(2, 3).collect::<Vec<_>>(), vec![2, 5, 8]);
+        assert!(signal.x(0).is_err());
+        assert_eq!(
+            signal.x(9).unwrap_err().to_string(),
+            "Cycle 9 is outside the program's 8 cycles"
+        );
+    }
+
+    #[test]
+    fn endless() {
+        let signal = Signal::new(&assemble("loop: addx 1\njmp loop").unwrap(), 20);
+        assert!(!signal.finished());
+        assert_eq!(signal.cycles(), 20);
+        assert_eq!(signal.x(20).unwrap(), 7);
+        assert_eq!(
+            signal.x(21).unwrap_err().to_string(),
+            "Cycle 21 is outside the 20 cycles the program was run for"
+        );
+        assert_eq!(signal.every(1, 1).count(), 20);
+    }
+
+    #[test]
+    fn overflow() {
+        let program = [Instruction::Addx(i32::MAX - 1), Instruction::Noop];
+        let signal = Signal::new(&program, 100);
+        assert_eq!(signal.strength(1).unwrap(), 1);
+        assert!(signal.strength(3).is_err());
+        let signal = Signal::new(&[Instruction::Addx((1 << 29) - 1), Instruction::Noop], 100);
+        assert_eq!(signal.strength(3).unwrap(), 3 << 29);
+        assert!(signal.total_strength([3, 3]).is_err());
+    }
+
+    #[test]
+    fn cycles_where() {
+        let signal = signal();
+        assert_eq!(signal.cycles_where(1), vec![1..=3]);
+        assert_eq!(signal.cycles_where(-1), vec![6..=8]);
+        assert!(signal.cycles_where(2).is_empty());
+
+        let signal = Signal::new(&assemble("addx 1\naddx -1\nnoop").unwrap(), 100);
+        assert_eq!(signal.cycles_where(1), vec![1..=2, 5..=5]);
+    }
+
+    #[test]
+    fn csv() {
+        let program = assemble("set b 2\nloop: add b -1\njnz b loop").unwrap();
+        let mut out = Vec::new();
+        write_csv(&program, 7, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "cycle,pc,instruction,x,b\n\
+             1,0,set b 2,1,0\n\
+             2,1,add b -1,1,2\n\
+             3,1,add b -1,1,2\n\
+             4,2,jnz b 1,1,1\n\
+             5,1,add b -1,1,1\n\
+             6,1,add b -1,1,1\n\
+             7,2,jnz b 1,1,0\n"
+        );
+
+        let program = assemble("jmp 0").unwrap();
+        let mut out = Vec::new();
+        assert_eq!(
+            write_csv(&program, 3, &mut out).unwrap_err().to_string(),
+            "The program is still running after 3 cycles"
+        );
+        assert_eq!(String::from_utf8(out).unwrap().lines().count(), 4);
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use super::{Instruction, Operand, Register};
+
+pub type Registers = [i32; Register::COUNT];
+
+/// A machine running a program, every register but `x`, which starts at 1,
+/// starting at 0. Adding wraps around, as a real machine's registers would,
+/// so looping programs can add forever.
+#[derive(Debug, Clone)]
+pub struct Vm<'a> {
+    program: &'a [Instruction],
+    registers: Registers,
+    pc: usize,
+    cycle: usize,
+}
+
+impl<'a> Vm<'a> {
+    pub fn new(program: &'a [Instruction]) -> Self {
+        let mut registers = [0; Register::COUNT];
+        registers[Register::X.index()] = 1;
+        Vm {
+            program,
+            registers,
+            pc: 0,
+            cycle: 0,
+        }
+    }
+
+    pub fn with_register(mut self, register: Register, value: i32) -> Self {
+        self.registers[register.index()] = value;
+        self
+    }
+
+    pub fn trace(self) -> Trace<'a> {
+        Trace {
+            vm: self,
+            remaining: 0,
+            halted: false,
+            breakpoints: HashSet::new(),
+            watchpoints: HashSet::new(),
+            last: None,
+        }
+    }
+
+    fn value(&self, operand: Operand) -> i32 {
+        match operand {
+            Operand::Register(register) => self.registers[register.index()],
+            Operand::Value(value) => value,
+        }
+    }
+
+    /// Carries out the instruction at `pc` once its cycles are up, returning
+    /// whether the machine halted.
+    fn execute(&mut self) -> bool {
+        let mut next = self.pc + 1;
+        match self.program[self.pc] {
+            Instruction::Noop => {}
+            Instruction::Addx(value) => {
+                let x = &mut self.registers[Register::X.index()];
+                *x = x.wrapping_add(value);
+            }
+            Instruction::Add(register, operand) => {
+                let value = self.value(operand);
+                let register = &mut self.registers[register.index()];
+                *register = register.wrapping_add(value);
+            }
+            Instruction::Set(register, operand) => {
+                self.registers[register.index()] = self.value(operand)
+            }
+            Instruction::Jmp(to) => next = to,
+            Instruction::Jz(register, to) => {
+                if self.registers[register.index()] == 0 {
+                    next = to;
+                }
+            }
+            Instruction::Jnz(register, to) => {
+                if self.registers[register.index()] != 0 {
+                    next = to;
+                }
+            }
+            Instruction::Halt => return true,
+        }
+        self.pc = next;
+        false
+    }
+}
+
+/// The machine during one cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// Counting from 1, as the puzzle does.
+    pub cycle: usize,
+    /// The instruction being carried out.
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub registers: Registers,
+}
+
+impl Step {
+    pub fn get(&self, register: Register) -> i32 {
+        self.registers[register.index()]
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cycle {:>4} pc {:>3} x {:>4} {}",
+            self.cycle,
+            self.pc,
+            self.get(Register::X),
+            self.instruction
+        )
+    }
+}
+
+/// Why `Trace::resume` stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// The instruction at a breakpoint is starting.
+    Breakpoint(Step),
+    /// A watched register changed since the cycle before.
+    Watchpoint {
+        step: Step,
+        register: Register,
+        was: i32,
+    },
+    /// The program ran off its end or halted.
+    Finished,
+}
+
+/// The machine cycle by cycle, registers only changing once an instruction's
+/// last cycle is over, as in the puzzle.
+#[derive(Debug, Clone)]
+pub struct Trace<'a> {
+    vm: Vm<'a>,
+    /// Cycles left of the instruction at `pc`, 0 when it is yet to start.
+    remaining: usize,
+    halted: bool,
+    breakpoints: HashSet<usize>,
+    watchpoints: HashSet<Register>,
+    last: Option<Step>,
+}
+
+impl Trace<'_> {
+    /// Stops `resume` when the instruction at `pc` starts.
+    pub fn break_at(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    /// Stops `resume` when `register` changes.
+    pub fn watch(&mut self, register: Register) {
+        self.watchpoints.insert(register);
+    }
+
+    /// Runs until a breakpoint or watchpoint, or the end of the program.
+    pub fn resume(&mut self) -> Stop {
+        while let Some(step) = self.next() {
+            let last = self.last.replace(step);
+            if self.remaining + 1 == step.instruction.cycles()
+                && self.breakpoints.contains(&step.pc)
+            {
+                return Stop::Breakpoint(step);
+            }
+            if let Some(last) = last {
+                let changed = self
+                    .watchpoints
+                    .iter()
+                    .filter(|register| last.get(**register) != step.get(**register))
+                    .min();
+                if let Some(&register) = changed {
+                    return Stop::Watchpoint {
+                        step,
+                        register,
+                        was: last.get(register),
+                    };
+                }
+            }
+        }
+        Stop::Finished
+    }
+
+    pub fn registers(&self) -> &Registers {
+        &self.vm.registers
+    }
+}
+
+impl Iterator for Trace<'_> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let vm = &mut self.vm;
+        if self.remaining == 0 {
+            if self.halted || vm.pc >= vm.program.len() {
+                return None;
+            }
+            self.remaining = vm.program[vm.pc].cycles();
+        }
+
+        vm.cycle += 1;
+        let step = Step {
+            cycle: vm.cycle,
+            pc: vm.pc,
+            instruction: vm.program[vm.pc],
+            registers: vm.registers,
+        };
+
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            self.halted = vm.execute();
+        }
+        Some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::asm::assemble;
+    use super::*;
+
+    /// Counts `a` down from 3, adding it to `x` each time.
+    const COUNTDOWN: &str = "
+        set a 3
+    loop:
+        jz a done
+        add x a
+        add a -1
+        jmp loop
+    done:
+        halt
+        addx 100
+    ";
+
+    #[test]
+    fn runs_and_halts() {
+        let program = assemble(COUNTDOWN).unwrap();
+        let mut trace = Vm::new(&program).trace();
+        let steps = trace.by_ref().collect::<Vec<_>>();
+        assert_eq!(steps.len(), 1 + 3 * 6 + 1 + 1);
+        assert_eq!(trace.registers()[Register::X.index()], 7);
+        assert_eq!(steps.last().unwrap().instruction, Instruction::Halt);
+        assert_eq!(steps[3].to_string(), "cycle    4 pc   2 x    1 add x a");
+        assert_eq!(steps[4].get(Register::X), 4);
+    }
+
+    #[test]
+    fn wraps_around() {
+        let program = assemble("loop: add x x\njmp loop").unwrap();
+        let mut trace = Vm::new(&program).trace();
+        let steps = trace.by_ref().take(3 * 32).collect::<Vec<_>>();
+        assert_eq!(steps[3 * 31].get(Register::X), i32::MIN);
+        assert_eq!(trace.registers()[Register::X.index()], 0);
+
+        let program = [Instruction::Addx(1)];
+        let mut trace = Vm::new(&program)
+            .with_register(Register::X, i32::MAX)
+            .trace();
+        trace.by_ref().for_each(drop);
+        assert_eq!(trace.registers()[Register::X.index()], i32::MIN);
+    }
+
+    #[test]
+    fn breakpoints() {
+        let program = assemble(COUNTDOWN).unwrap();
+        let mut trace = Vm::new(&program).trace();
+        trace.break_at(3);
+
+        let cycles = std::iter::from_fn(|| match trace.resume() {
+            Stop::Breakpoint(step) => Some((step.cycle, step.get("a".parse().unwrap()))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+        assert_eq!(cycles, vec![(5, 3), (11, 2), (17, 1)]);
+    }
+
+    #[test]
+    fn watchpoints() {
+        let program = assemble(COUNTDOWN).unwrap();
+        let mut trace = Vm::new(&program).trace();
+        trace.watch(Register::X);
+
+        let mut changes = Vec::new();
+        while let Stop::Watchpoint { step, was, .. } = trace.resume() {
+            changes.push((step.cycle, was, step.get(Register::X)));
+        }
+        assert_eq!(changes, vec![(5, 1, 4), (11, 4, 6), (17, 6, 7)]);
+    }
+}
@@ -7,6 +7,7 @@ use anyhow::Result;
 use itertools::Itertools;
 
 use aoc::collect_statements;
+use aoc::input_file;
 use aoc::time_it;
 
 const NUM_ROUNDS: usize = 20;
@@ -17,7 +18,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let mut monkeys = collect_statements::<Monkey>("input/day11.txt")?;
+    let mut monkeys = collect_statements::<Monkey>(input_file(11)?)?;
 
     for _ in 0..NUM_ROUNDS {
         for idx in 0..monkeys.len() {
@@ -9,6 +9,7 @@ use anyhow::anyhow;
 use anyhow::Error;
 use anyhow::Result;
 
+use aoc::input_file;
 use aoc::read_and_parse;
 use aoc::time_it;
 use itertools::Itertools;
@@ -21,7 +22,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let grid = read_and_parse::<Grid>("input/day12.txt")?;
+    let grid = read_and_parse::<Grid>(input_file(12)?)?;
     let visitor = Visitor::new(grid);
     Ok(visitor.count())
 }
@@ -5,6 +5,7 @@ use anyhow::anyhow;
 use anyhow::Error;
 use anyhow::Result;
 
+use aoc::input_file;
 use aoc::read_and_parse;
 use aoc::time_it;
 
@@ -14,7 +15,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let grid = read_and_parse::<Grid>("input/day12.txt")?;
+    let grid = read_and_parse::<Grid>(input_file(12)?)?;
     let visitor = Visitor::new(grid);
     Ok(visitor.count())
 }
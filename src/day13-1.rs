@@ -7,6 +7,7 @@ use anyhow::Error;
 use anyhow::Result;
 
 use aoc::collect_statements;
+use aoc::input_file;
 use aoc::time_it;
 use itertools::Itertools;
 
@@ -16,7 +17,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let packet_pairs = collect_statements::<PacketPair>("input/day13.txt")?;
+    let packet_pairs = collect_statements::<PacketPair>(input_file(13)?)?;
     Ok(packet_pairs
         .into_iter()
         .enumerate()
@@ -10,6 +10,7 @@ use anyhow::Error;
 use anyhow::Result;
 
 use aoc::collect_lines;
+use aoc::input_file;
 use aoc::time_it;
 
 // const SLEEP: time::Duration = time::Duration::from_millis(30);
@@ -20,7 +21,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let rock_paths = collect_lines::<RockPath>("input/day14.txt")?;
+    let rock_paths = collect_lines::<RockPath>(input_file(14)?)?;
     let mut cave = Cave::new();
 
     for path in rock_paths.into_iter() {
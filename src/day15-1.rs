@@ -8,6 +8,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 use aoc::collect_lines;
+use aoc::input_file;
 use aoc::time_it;
 use aoc::Point;
 
@@ -20,7 +21,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let readings = collect_lines::<Reading>("input/day15.txt")?;
+    let readings = collect_lines::<Reading>(input_file(15)?)?;
     let mut vacancies = Vacancies::new();
 
     for reading in &readings {
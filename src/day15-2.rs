@@ -7,6 +7,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 use aoc::collect_lines;
+use aoc::input_file;
 use aoc::time_it;
 use aoc::Point;
 
@@ -18,7 +19,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<u64> {
-    let readings = collect_lines::<Reading>("input/day15.txt")?;
+    let readings = collect_lines::<Reading>(input_file(15)?)?;
 
     let rslt = readings
         .iter()
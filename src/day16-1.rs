@@ -10,6 +10,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 
 use aoc::collect_lines;
+use aoc::input_file;
 use aoc::time_it;
 
 fn main() -> Result<()> {
@@ -18,7 +19,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let readings = collect_lines::<Reading>("input/day16.txt")?;
+    let readings = collect_lines::<Reading>(input_file(16)?)?;
     let start = readings
         .iter()
         .position(|reading| reading.valve == "AA")
@@ -3,12 +3,14 @@ use std::collections::HashSet;
 use std::fmt::Display;
 use std::iter::Cycle;
 use std::ops::Add;
+use std::path::Path;
 use std::vec::IntoIter;
 
 use anyhow::anyhow;
 use anyhow::Error;
 use anyhow::Result;
 
+use aoc::input_file;
 use aoc::time_it;
 use aoc::Point;
 use itertools::Itertools;
@@ -44,7 +46,7 @@ fn main() -> Result<()> {
  */
 
 fn solution() -> Result<i32> {
-    let shifts = read_shifts("input/day17.txt")?;
+    let shifts = read_shifts(input_file(17)?)?;
     let mut game = GameIter::new(FLOOR_SIZE, shifts);
 
     Ok(game.nth(NUM_ROCKS - 1).unwrap())
@@ -183,7 +185,7 @@ impl Game {
     }
 }
 
-fn read_shifts(fname: &str) -> Result<Vec<Shift>> {
+fn read_shifts(fname: impl AsRef<Path>) -> Result<Vec<Shift>> {
     std::fs::read_to_string(fname)?
         .trim()
         .chars()
@@ -3,6 +3,7 @@ use std::collections::HashSet;
 use std::fmt::Display;
 use std::iter::Cycle;
 use std::ops::Add;
+use std::path::Path;
 use std::vec::IntoIter;
 
 use anyhow::anyhow;
@@ -10,6 +11,7 @@ use anyhow::Error;
 use anyhow::Result;
 use itertools::Itertools;
 
+use aoc::input_file;
 use aoc::time_it;
 use aoc::Divmod;
 use aoc::Point;
@@ -44,7 +46,7 @@ fn main() -> Result<()> {
  */
 
 fn solution() -> Result<usize> {
-    let shifts = read_shifts("input/day17.txt")?;
+    let shifts = read_shifts(input_file(17)?)?;
     // let shifts = read_shifts("example.txt")?;
     let num_rocks = shifts.len() * 5;
     let mut game = GameIter::new(FLOOR_SIZE, shifts.clone());
@@ -245,7 +247,7 @@ impl Game {
     }
 }
 
-fn read_shifts(fname: &str) -> Result<Vec<Shift>> {
+fn read_shifts(fname: impl AsRef<Path>) -> Result<Vec<Shift>> {
     std::fs::read_to_string(fname)?
         .trim()
         .chars()
@@ -10,6 +10,7 @@ use anyhow::Result;
 use itertools::Itertools;
 
 use aoc::collect_lines;
+use aoc::input_file;
 use aoc::time_it;
 
 type Faces = [Point; 6];
@@ -28,7 +29,7 @@ fn main() -> Result<()> {
 }
 
 fn solution() -> Result<usize> {
-    let droplets = collect_lines::<Droplet>("input/day18.txt")?;
+    let droplets = collect_lines::<Droplet>(input_file(18)?)?;
     let face_counter = droplets
         .into_iter()
         .map(|droplet| droplet.faces())
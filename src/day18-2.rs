@@ -12,6 +12,7 @@ use anyhow::Result;
 use itertools::Itertools;
 
 use aoc::collect_lines;
+use aoc::input_file;
 use aoc::time_it;
 
 type Faces = [Point; 6];
@@ -31,7 +32,7 @@ fn main() -> Result<()> {
 
 fn solution() -> Result<usize> {
     // let mut droplets = collect_lines::<Droplet>("example.txt")?;
-    let mut droplets = collect_lines::<Droplet>("input/day18.txt")?;
+    let mut droplets = collect_lines::<Droplet>(input_file(18)?)?;
     let air_pockets = find_air_pockets(&droplets);
     for air_pocket in air_pockets {
         droplets.push(air_pocket.into());
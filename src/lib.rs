@@ -1,26 +1,41 @@
 use anyhow::Result;
 use std::fmt::Debug;
 use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Sub, SubAssign};
+use std::path::Path;
 use std::str::FromStr;
 use std::time;
 
-pub fn collect_lines<T>(fname: &str) -> Result<Vec<T>>
+pub mod config;
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+
+pub use config::input_file;
+
+pub fn collect_lines<T>(fname: impl AsRef<Path>) -> Result<Vec<T>>
 where
     T: FromStr,
     <T as FromStr>::Err: Debug,
 {
-    Ok(std::fs::read_to_string(&fname)?
+    Ok(std::fs::read_to_string(fname)?
         .lines()
         .map(|line| line.parse::<T>().expect("Failed to parse line."))
         .collect())
 }
 
-pub fn collect_blocks<T>(fname: &str) -> Result<Vec<Vec<T>>>
+pub fn collect_blocks<T>(fname: impl AsRef<Path>) -> Result<Vec<Vec<T>>>
 where
     T: FromStr,
     <T as FromStr>::Err: Debug,
 {
-    Ok(std::fs::read_to_string(&fname)?
+    Ok(std::fs::read_to_string(fname)?
         .split("\n\n")
         .map(|block| {
             block
@@ -31,23 +46,23 @@ where
         .collect())
 }
 
-pub fn collect_statements<T>(fname: &str) -> Result<Vec<T>>
+pub fn collect_statements<T>(fname: impl AsRef<Path>) -> Result<Vec<T>>
 where
     T: FromStr,
     <T as FromStr>::Err: Debug,
 {
-    Ok(std::fs::read_to_string(&fname)?
+    Ok(std::fs::read_to_string(fname)?
         .split("\n\n")
         .map(|block| block.parse().expect("Failed to parse statement."))
         .collect())
 }
 
-pub fn read_and_parse<T>(fname: &str) -> Result<T>
+pub fn read_and_parse<T>(fname: impl AsRef<Path>) -> Result<T>
 where
     T: FromStr<Err = anyhow::Error>,
     <T as FromStr>::Err: Debug,
 {
-    std::fs::read_to_string(&fname)?.parse()
+    std::fs::read_to_string(fname)?.parse()
 }
 
 pub fn time_it<F, R>(func: F) -> Result<()>